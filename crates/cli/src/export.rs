@@ -0,0 +1,76 @@
+//! `export`: write a cheat sheet of the effective binds (CSV, Markdown or HTML).
+
+use std::{fs, path::PathBuf, sync::Arc};
+
+use clap::Args;
+
+use streamdeck_sc_core::prelude::*;
+
+use crate::lookup::LookupArgs;
+
+fn parse_format_arg(s: &str) -> Result<SheetFormat, String> {
+    SheetFormat::parse(s).ok_or_else(|| "expected one of: csv, md, html".into())
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// Output format: csv | md | html (defaults to the --out extension, else md)
+    #[arg(long, value_parser = parse_format_arg)]
+    pub format: Option<SheetFormat>,
+
+    /// Where to write the sheet (stdout if missing)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Page/document title
+    #[arg(long, default_value = "Star Citizen binds")]
+    pub title: String,
+
+    /// Leave out actions without any bind
+    #[arg(long)]
+    pub only_bound: bool,
+
+    /// Only actions with a generated bind (implies --generated)
+    #[arg(long)]
+    pub only_generated: bool,
+}
+
+pub fn run(mut args: ExportArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    args.lookup.generated |= args.only_generated;
+    let (ab, translations) = args.lookup.load(core_log)?;
+
+    let sheet = CheatSheet::build(
+        &ab,
+        &translations,
+        SheetOptions {
+            only_bound: args.only_bound,
+            only_generated: args.only_generated,
+        },
+    );
+    if sheet.is_empty() {
+        core_log.warn("No actions left to export");
+    }
+
+    let format = args
+        .format
+        .or_else(|| {
+            args.out
+                .as_ref()
+                .and_then(|p| p.extension())
+                .and_then(|e| SheetFormat::parse(&e.to_string_lossy()))
+        })
+        .unwrap_or(SheetFormat::Markdown);
+    let text = sheet.render(format, &args.title);
+
+    match args.out.as_ref() {
+        Some(out) => {
+            fs::write(out, text).map_err(|e| format!("write {}: {e}", out.display()))?;
+            core_log.info(&format!("✅ Wrote {}", out.display()));
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! `generate`: write a mappings XML with missing binds filled in.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use clap::Args;
+
+use streamdeck_sc_core::prelude::*;
+
+use crate::{profile::ProfileArgs, report::print_conflicts};
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    #[command(flatten)]
+    pub profile: ProfileArgs,
+
+    /// Output path for the resulting mappings XML (defaults next to custom, else next to default)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Profile label used in <CustomisationUIHeader label="">
+    #[arg(long)]
+    pub profile_name: Option<String>,
+
+    /// Print binds your custom or generated binds share with other actions in the same
+    /// category group (after generation)
+    #[arg(long)]
+    pub conflicts: bool,
+
+    /// Like --conflicts, but print the full report (defaults included) as JSON
+    #[arg(long)]
+    pub conflicts_json: bool,
+}
+
+pub fn run(args: GenerateArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    let loaded = args.profile.load(true, args.out.is_none(), core_log)?;
+    let ab = loaded.bindings;
+
+    if args.conflicts || args.conflicts_json {
+        let report = ab.conflicts_with(&loaded.config);
+        if args.conflicts_json {
+            println!("{}", report.to_json()?);
+        } else {
+            print_conflicts(&report);
+        }
+    }
+
+    // Resolve output path if not explicitly given
+    let out_path = if let Some(o) = args.out.as_ref() {
+        o.clone()
+    } else if let Some(root) = loaded.game_root.as_ref() {
+        let profile_dir = root
+            .join("user")
+            .join("client")
+            .join("0")
+            .join("controls")
+            .join("mappings");
+        derive_output_path(&profile_dir)
+    } else {
+        derive_output_path(&args.profile.default)
+    };
+
+    // Emit XML (devices default internally to keyboard=1/mouse=1)
+    if let Some(parent) = out_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("create output dir {}: {e}", parent.display()))?;
+    }
+    let profile_label = args.profile_name.unwrap_or_else(default_profile_label);
+    ab.generate_mapping_xml(&out_path, None, &profile_label)?;
+    core_log.info(&format!("✅ Wrote {}", out_path.display()));
+
+    if let Some(state_path) = args.profile.state.as_ref() {
+        fs::write(state_path, ab.to_json()?)
+            .map_err(|e| format!("write {}: {e}", state_path.display()))?;
+        core_log.info(&format!("✅ Wrote {}", state_path.display()));
+    }
+    Ok(())
+}
+
+fn default_profile_label() -> String {
+    let ts = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("Generated {ts} (scmap-gen)")
+}
+
+fn derive_output_path(anchor: &Path) -> PathBuf {
+    if anchor.is_dir() {
+        anchor.join("mappings-generated.xml")
+    } else {
+        anchor
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("mappings-generated.xml")
+    }
+}
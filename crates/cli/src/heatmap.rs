@@ -0,0 +1,63 @@
+//! `heatmap`: draw which keys are taken in one category's context as an SVG.
+
+use std::{collections::HashSet, fs, path::PathBuf, sync::Arc};
+
+use clap::Args;
+
+use streamdeck_sc_core::prelude::*;
+
+use crate::lookup::LookupArgs;
+
+#[derive(Args, Debug)]
+pub struct HeatmapArgs {
+    /// UI category whose group to draw, e.g. "@ui_CCSpaceFlight" (the "@ui_" prefix may
+    /// be left out), or a context name like "flight" or "fps"
+    pub category: String,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// Where to write the SVG (stdout if missing)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+pub fn run(args: HeatmapArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    let (ab, translations) = args.lookup.load(core_log)?;
+    let group_map = args.lookup.group_map()?;
+
+    let category = resolve_context(&args.category);
+    let known: HashSet<&str> = ab
+        .action_maps
+        .values()
+        .map(|m| m.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY))
+        .chain(group_map.keys().map(String::as_str))
+        .collect();
+    if !known.contains(category.as_str()) {
+        let mut known: Vec<&str> = known.into_iter().collect();
+        known.sort();
+        return Err(format!(
+            "unknown category '{category}' (known: {})",
+            known.join(", ")
+        ));
+    }
+
+    let heatmap = KeyboardHeatmap::build(&ab, &translations, &category, &group_map);
+    for layer in &heatmap.layers {
+        core_log.debug(&format!("{}: {} keys used", layer.name(), layer.keys.len()));
+    }
+    let svg = heatmap.to_svg();
+
+    match args.out.as_ref() {
+        Some(out) => {
+            fs::write(out, svg).map_err(|e| format!("write {}: {e}", out.display()))?;
+            core_log.info(&format!(
+                "✅ Wrote {} ({} layers)",
+                out.display(),
+                heatmap.layers.len()
+            ));
+        }
+        None => print!("{svg}"),
+    }
+    Ok(())
+}
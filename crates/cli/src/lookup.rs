@@ -0,0 +1,274 @@
+//! `search`, `show` and `who-uses`: find actions and print what they're bound to.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use clap::Args;
+
+use streamdeck_sc_core::bindings::translations::{
+    get_translation, load_translations_cached_from_bindings,
+};
+use streamdeck_sc_core::prelude::*;
+
+use crate::profile::ProfileArgs;
+
+#[derive(Args, Debug)]
+pub struct LookupArgs {
+    #[command(flatten)]
+    pub profile: ProfileArgs,
+
+    /// The game's global.ini, for translated labels and descriptions (ids and raw
+    /// label keys are used without it)
+    #[arg(long, value_name = "PATH")]
+    pub global_ini: Option<PathBuf>,
+
+    /// Fill missing binds first (with --bindgen-config/--state), to see generated binds
+    #[arg(long)]
+    pub generated: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Words to look for in action ids, labels and descriptions (case-insensitive, fuzzy)
+    #[arg(required = true)]
+    pub query: Vec<String>,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+
+    /// Most results to print
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Action id, e.g. "spaceship_general.v_flightready"
+    pub action_id: String,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct WhoUsesArgs {
+    /// Bind as the profile writes it, e.g. "lalt+f", "mouse4", "js1_button3"
+    pub bind: String,
+
+    /// Only actions that share binds with this context: flight, seat, vehicle, turret,
+    /// fps, eva, map, camera, spectator, ui, or a UI category ("@ui_CCSpaceFlight")
+    #[arg(long)]
+    pub context: Option<String>,
+
+    #[command(flatten)]
+    pub lookup: LookupArgs,
+}
+
+impl LookupArgs {
+    /// Bindings (see `ProfileArgs::load`) plus translations, if `--global-ini` is given.
+    pub fn load(
+        &self,
+        core_log: &Arc<dyn CoreLog>,
+    ) -> Result<(ActionBindings, HashMap<String, String>), String> {
+        let ab = self.profile.load(self.generated, false, core_log)?.bindings;
+        let translations = match self.global_ini.as_ref() {
+            Some(ini) => load_translations_cached_from_bindings(
+                ini.clone(),
+                &ab,
+                translations_cache_path(ini),
+                core_log,
+            ),
+            None => HashMap::new(),
+        };
+        Ok((ab, translations))
+    }
+
+    /// Category groups of `--bindgen-config`, else `CATEGORY_GROUPS`.
+    pub fn group_map(&self) -> Result<HashMap<String, HashSet<String>>, String> {
+        Ok(match self.profile.bindgen_config.as_ref() {
+            Some(p) => BindGenConfig::load(p)?.category_group_map(),
+            None => CATEGORY_GROUPS.clone(),
+        })
+    }
+}
+
+/// Translations cache for one `global.ini`: keyed by its path, so switching to another
+/// language or install never reads the other file's strings. The key is an FNV-1a hash
+/// of the canonical path, which (unlike `DefaultHasher`) stays the same across Rust
+/// releases.
+fn translations_cache_path(ini: &Path) -> PathBuf {
+    let path = ini.canonicalize().unwrap_or_else(|_| ini.to_path_buf());
+    let hash = path
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    std::env::temp_dir().join(format!("scmap-gen-translations-{hash:016x}.json"))
+}
+
+pub fn search(args: SearchArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    let (ab, translations) = args.lookup.load(core_log)?;
+    let hits = search_actions(&ab, &translations, &args.query.join(" "));
+    if hits.is_empty() {
+        println!("No matching actions.");
+        return Ok(());
+    }
+
+    let id_width = hits
+        .iter()
+        .take(args.limit)
+        .map(|h| h.action.action_id.len())
+        .max()
+        .unwrap_or(0);
+    for hit in hits.iter().take(args.limit) {
+        let binds = hit.action.get_binds_label().unwrap_or_else(|| "-".into());
+        println!(
+            "{:<id_width$}  {:<40}  {binds}",
+            hit.action.action_id, hit.label
+        );
+    }
+    if hits.len() > args.limit {
+        println!("... {} more (raise --limit)", hits.len() - args.limit);
+    }
+    Ok(())
+}
+
+pub fn show(args: ShowArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    let (ab, translations) = args.lookup.load(core_log)?;
+    let Some(action) = ab.get_action(&args.action_id) else {
+        let guesses: Vec<String> = search_actions(&ab, &translations, &args.action_id)
+            .iter()
+            .take(3)
+            .map(|h| h.action.action_id.to_string())
+            .collect();
+        return Err(if guesses.is_empty() {
+            format!("unknown action '{}'", args.action_id)
+        } else {
+            format!(
+                "unknown action '{}' (did you mean {}?)",
+                args.action_id,
+                guesses.join(", ")
+            )
+        });
+    };
+    let map = args
+        .action_id
+        .split_once('.')
+        .and_then(|(m, _)| ab.action_maps.get(m));
+
+    println!("{}", action.action_id);
+    println!("  Label:       {}", action.get_label(&translations));
+    if let Some(desc) = action.ui_description.as_deref() {
+        println!("  Description: {}", get_translation(desc, &translations));
+    }
+    if let Some(map) = map {
+        println!(
+            "  Action map:  {} (version {})",
+            map.get_label(&translations),
+            map.version
+        );
+        let category = map.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+        println!(
+            "  Category:    {}",
+            get_translation(category, &translations)
+        );
+    }
+    if let Some(category) = action.category.as_deref() {
+        println!("  Subcategory: {category}");
+    }
+    let mode = action
+        .activation_mode
+        .and_then(|ix| ab.activation.get(ix))
+        .map(|m| m.display_name().into_owned());
+    println!("  Activation:  {}", mode.as_deref().unwrap_or("-"));
+    if let Some(states) = action.get_states_label(&translations) {
+        println!("  States:      {states}");
+    }
+    let groups: Vec<&str> = ab
+        .action_groups
+        .values()
+        .filter(|g| g.members.contains(&action.action_id))
+        .map(|g| g.name.as_ref())
+        .collect();
+    if !groups.is_empty() {
+        println!("  Groups:      {}", groups.join(", "));
+    }
+
+    print_binds(&ab, "Default", &action.default_binds, |b| !b.is_unbound);
+    if let Some(custom) = action.custom_binds.as_ref() {
+        print_binds(&ab, "Custom", custom, |b| b.origin == BindOrigin::User);
+        print_binds(&ab, "Generated", custom, |b| {
+            b.origin == BindOrigin::Generated
+        });
+    }
+    Ok(())
+}
+
+pub fn who_uses(args: WhoUsesArgs, core_log: &Arc<dyn CoreLog>) -> Result<(), String> {
+    let (ab, translations) = args.lookup.load(core_log)?;
+    let index = ab.into_index();
+    let mut uses = index.by_bind_str(&args.bind)?;
+
+    if let Some(context) = args.context.as_deref() {
+        let category = resolve_context(context);
+        if !index.categories().contains(&category.as_str()) {
+            return Err(format!(
+                "unknown context '{context}' (categories: {})",
+                index.categories().join(", ")
+            ));
+        }
+        let shared = index.group_categories(&category, &args.lookup.group_map()?);
+        uses.retain(|u| shared.contains(u.category));
+    }
+
+    if uses.is_empty() {
+        println!("Nothing uses {}.", args.bind);
+        return Ok(());
+    }
+    let id_width = uses
+        .iter()
+        .map(|u| u.action.action_id.len())
+        .max()
+        .unwrap_or(0);
+    for u in &uses {
+        println!(
+            "{:<id_width$}  {:<40}  {:<9} {:<9} {:<20} {}",
+            u.action.action_id,
+            u.action.get_label(&translations),
+            u.device.name(),
+            u.source.name(),
+            u.activation_mode.unwrap_or("-"),
+            u.category
+        );
+    }
+    Ok(())
+}
+
+fn print_binds(ab: &ActionBindings, title: &str, binds: &Binds, keep: impl Fn(&&Bind) -> bool) {
+    let lines: Vec<String> = DeviceKind::ALL
+        .iter()
+        .flat_map(|&d| {
+            binds.device(d).iter().filter(&keep).map(move |b| {
+                let bind = if b.is_unbound {
+                    "(unbound)".to_string()
+                } else {
+                    b.to_string()
+                };
+                let mode = b
+                    .activation_mode_idx
+                    .and_then(|ix| ab.activation.get(ix))
+                    .map(|m| format!(" [{}]", m.display_name()))
+                    .unwrap_or_default();
+                format!("{d}: {bind}{mode}")
+            })
+        })
+        .collect();
+    if !lines.is_empty() {
+        println!("  {:<12} {}", format!("{title}:"), lines.join(", "));
+    }
+}
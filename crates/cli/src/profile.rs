@@ -0,0 +1,179 @@
+//! Loading the bindings graph (defaults + optional custom profile + generated binds),
+//! shared by the subcommands.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use clap::Args;
+
+use streamdeck_sc_core::prelude::*;
+use streamdeck_sc_core::sc::install::{choose_install_root, scan_paths_and_active};
+use streamdeck_sc_core::sc::profiles::resolve_custom_profile_from_root;
+
+fn parse_install_arg(s: &str) -> Result<GameInstallType, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "live" => Ok(GameInstallType::Live),
+        "ptu" => Ok(GameInstallType::Ptu),
+        "tech" | "techpreview" | "tp" => Ok(GameInstallType::TechPreview),
+        _ => Err("expected one of: live, ptu, tech".into()),
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ProfileArgs {
+    /// Path to the default profile XML (game "defaultProfile.xml")
+    #[arg(short, long)]
+    pub default: PathBuf,
+
+    /// Include custom profile (if found/resolved)
+    #[arg(short, long)]
+    pub include_custom: bool,
+
+    /// Custom profile to use (defaults to resolved from install root, if any)
+    #[arg(long, short)]
+    pub custom: Option<PathBuf>,
+
+    /// Which install to prefer for resolving the custom profile: live | ptu | tech
+    #[arg(long, default_value = "live", value_parser = parse_install_arg)]
+    pub install: GameInstallType,
+
+    /// Optional JSON to override bind-generation pools/rules (core `BindGenConfig`; the plugin
+    /// reads the same format from AppData/bindgen.json)
+    #[arg(long, value_name = "PATH")]
+    pub bindgen_config: Option<PathBuf>,
+
+    /// JSON cache of the previous run: generated binds in it are kept where still free,
+    /// and `generate` rewrites it (same format as the plugin's AppData cache)
+    #[arg(long, value_name = "PATH")]
+    pub state: Option<PathBuf>,
+
+    /// Write what loading the profiles dropped or kept verbatim to PATH as JSON (one load
+    /// report per profile read, defaults first)
+    #[arg(long, value_name = "PATH")]
+    pub load_report: Option<PathBuf>,
+}
+
+/// A loaded profile plus what was resolved along the way.
+pub struct Loaded {
+    pub bindings: ActionBindings,
+    pub config: BindGenConfig,
+    /// Install root from the launcher log, if one had to be resolved.
+    pub game_root: Option<PathBuf>,
+}
+
+impl ProfileArgs {
+    /// Load defaults (+ custom if `--include-custom`), then fill missing binds if `generate`.
+    /// `need_root` resolves the install root even when `--custom` is given (for outputs).
+    pub fn load(
+        &self,
+        generate: bool,
+        need_root: bool,
+        core_log: &Arc<dyn CoreLog>,
+    ) -> Result<Loaded, String> {
+        core_log.info(&format!("Default profile: {}", self.default.display()));
+
+        // Validate default profile
+        if !self.default.is_file() {
+            return Err(format!(
+                "--default not found or not a file: {}",
+                self.default.display()
+            ));
+        }
+
+        // If the custom profile isn't given, resolve a game root from the launcher log
+        let game_root = if need_root || (self.include_custom && self.custom.is_none()) {
+            match scan_paths_and_active() {
+                Ok((map, last_active)) => choose_install_root(&map, self.install, last_active),
+                Err(e) => {
+                    core_log.warn(&format!("scan_paths_and_active failed: {e}"));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Load defaults (+ optional custom), using core graph
+        let mut ab = ActionBindings::default();
+        let mut reports = vec![ab.load_default_profile(
+            &self.default,
+            &SKIP_ACTION_MAPS,
+            &ACTION_MAP_UI_CATEGORIES,
+            core_log,
+        )?];
+
+        if self.include_custom {
+            match self.custom_profile(game_root.as_deref(), core_log) {
+                Some(cf) if cf.try_exists().unwrap_or(false) => {
+                    match ab.apply_custom_profile(&cf, core_log) {
+                        Ok(report) => reports.push(report),
+                        Err(e) => core_log.warn(&format!(
+                            "apply_custom_profile({}): {e}",
+                            self.install.name()
+                        )),
+                    }
+                }
+                Some(cf) => core_log.warn(&format!("custom file missing at {}", cf.display())),
+                None => core_log.warn("No custom profile will be applied"),
+            }
+        }
+        ab.activation.rebuild_indexes();
+
+        for report in &reports {
+            report.log(core_log);
+        }
+        if let Some(out) = self.load_report.as_ref() {
+            let text = serde_json::to_string_pretty(&reports)
+                .map_err(|e| format!("serialize load report: {e}"))?;
+            fs::write(out, text).map_err(|e| format!("write {}: {e}", out.display()))?;
+        }
+
+        let config = match self.bindgen_config.as_ref() {
+            Some(p) => BindGenConfig::load(p)?,
+            None => BindGenConfig::default(),
+        };
+
+        if generate {
+            // Binds generated by the previous run stay where they are
+            let previous = match self.state.as_ref() {
+                Some(p) if p.try_exists().unwrap_or(false) => {
+                    let text =
+                        fs::read_to_string(p).map_err(|e| format!("read {}: {e}", p.display()))?;
+                    Some(ActionBindings::from_json(&text, core_log)?)
+                }
+                _ => None,
+            };
+            ab.generate_missing_binds_with(&config, previous.as_ref(), core_log);
+        }
+
+        Ok(Loaded {
+            bindings: ab,
+            config,
+            game_root,
+        })
+    }
+
+    /// `--custom`, else the profile resolved from the install root.
+    fn custom_profile(
+        &self,
+        game_root: Option<&Path>,
+        core_log: &Arc<dyn CoreLog>,
+    ) -> Option<PathBuf> {
+        if let Some(c) = self.custom.as_ref() {
+            return Some(c.clone());
+        }
+        let root = game_root?;
+        let p = resolve_custom_profile_from_root(root);
+        match p.as_ref() {
+            Some(pp) => core_log.info(&format!("Resolved custom profile: {}", pp.display())),
+            None => core_log.warn(&format!(
+                "Could not resolve custom profile from install root: {}",
+                root.display()
+            )),
+        }
+        p
+    }
+}
@@ -0,0 +1,100 @@
+//! Text renderings of the core reports (conflicts, diff, changelog).
+
+use streamdeck_sc_core::prelude::*;
+
+pub fn print_conflicts(report: &ConflictReport) {
+    let side = |s: &ConflictSide| {
+        let mode = s.activation_mode.as_deref().unwrap_or("-");
+        format!("{} ({}, {mode})", s.action_id, s.origin)
+    };
+    let mut shown = 0;
+    for c in report.user_conflicts() {
+        println!(
+            "{:<20} {:<8} {}  <->  {}",
+            c.bind,
+            c.device,
+            side(&c.first),
+            side(&c.second)
+        );
+        shown += 1;
+    }
+    println!(
+        "{shown} conflicts with custom/generated binds ({} between defaults not shown)",
+        report.len() - shown
+    );
+}
+
+pub fn print_diff(diff: &ProfileDiff) {
+    for action in &diff.actions {
+        println!("{} {}", action.status.name(), action.action_id);
+        print_bind_changes(action);
+    }
+    println!(
+        "{} added, {} removed, {} changed",
+        diff.count(DiffStatus::Added),
+        diff.count(DiffStatus::Removed),
+        diff.count(DiffStatus::Changed)
+    );
+}
+
+fn print_bind_changes(action: &ActionDiff) {
+    for c in &action.changes {
+        let mode = c.activation_mode.as_deref().unwrap_or("-");
+        for b in &c.removed {
+            println!("    - {:<8} {:<24} [{mode}]", c.device, b);
+        }
+        for b in &c.added {
+            println!("    + {:<8} {:<24} [{mode}]", c.device, b);
+        }
+    }
+}
+
+pub fn print_changelog(log: &DefaultsChangelog) {
+    if log.is_empty() {
+        println!("No changes.");
+        return;
+    }
+    let section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            println!("## {title} ({})", lines.len());
+            for l in lines {
+                println!("  {l}");
+            }
+            println!();
+        }
+    };
+    let renamed = |r: &Rename| format!("~ {} -> {}", r.old, r.new);
+
+    section(
+        "Action maps",
+        log.maps_added
+            .iter()
+            .map(|m| format!("+ {m}"))
+            .chain(log.maps_removed.iter().map(|m| format!("- {m}")))
+            .chain(log.maps_renamed.iter().map(renamed))
+            .collect(),
+    );
+    section(
+        "Version bumps (custom rebinds in these maps are dropped by the game)",
+        log.version_bumps
+            .iter()
+            .map(|v| format!("! {}: {} -> {}", v.action_map, v.old, v.new))
+            .collect(),
+    );
+    section(
+        "Actions",
+        log.actions_added
+            .iter()
+            .map(|a| format!("+ {a}"))
+            .chain(log.actions_removed.iter().map(|a| format!("- {a}")))
+            .chain(log.actions_renamed.iter().map(renamed))
+            .collect(),
+    );
+    if !log.binds_changed.is_empty() {
+        println!("## Default binds ({})", log.binds_changed.len());
+        for action in &log.binds_changed {
+            println!("  {} {}", action.status.name(), action.action_id);
+            print_bind_changes(action);
+        }
+    }
+}
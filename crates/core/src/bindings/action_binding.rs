@@ -1,6 +1,6 @@
 use crate::bindings::{
     activation_mode::{ActivationArena, ActivationMode},
-    bind::BindParseError,
+    bind::{Bind, BindOrigin, BindParseError, BindSource, DeviceKind},
     binds::Binds,
     str_intern::intern,
     translations::get_translation,
@@ -14,6 +14,22 @@ pub enum ActionBindingParseError {
     MissingName,
 }
 
+/// One entry of an action's `<states>` block (toggles like `v_power_toggle`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionState {
+    pub name: Arc<str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_label: Option<Arc<str>>,
+}
+
+impl ActionState {
+    /// Prefer localized label; fall back to the state `name`.
+    pub fn get_label(&self, translations: &HashMap<String, String>) -> String {
+        let key = self.ui_label.as_deref().unwrap_or(&self.name);
+        get_translation(key, translations).to_string()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionBinding {
     pub action_id: Arc<str>,
@@ -34,6 +50,15 @@ pub struct ActionBinding {
     /// (Action-level fallback when a bind doesn't have its own.)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub activation_mode: Option<usize>,
+
+    /// Name of the `<optiongroup>` holding this action's axis tuning (`optionGroup=`).
+    /// Resolve with `ActionBindings::option_groups`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub option_group: Option<Arc<str>>,
+
+    /// Toggle states in source order (empty for plain actions).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<ActionState>,
 }
 
 impl ActionBinding {
@@ -65,6 +90,7 @@ impl ActionBinding {
         let ui_label = Self::non_empty_attr(node, "UILabel").map(intern);
         let ui_description = Self::non_empty_attr(node, "UIDescription").map(intern);
         let category = Self::non_empty_attr(node, "Category").map(intern);
+        let option_group = Self::non_empty_attr(node, "optionGroup").map(intern);
 
         // Binds resolve their own bind-level activation modes into arena indices.
         let (default_binds, bind_errors) = Binds::from_node(node, activation_arena);
@@ -72,6 +98,18 @@ impl ActionBinding {
         // Action-level activation mode (fallback for binds that don’t specify a mode)
         let action_level_mode = ActivationMode::resolve(node, None, activation_arena);
 
+        let states = node
+            .children()
+            .filter(|n| n.has_tag_name("states"))
+            .flat_map(|n| n.children().filter(|c| c.has_tag_name("state")))
+            .filter_map(|st| {
+                Some(ActionState {
+                    name: intern(Self::non_empty_attr(st, "name")?),
+                    ui_label: Self::non_empty_attr(st, "UILabel").map(intern),
+                })
+            })
+            .collect();
+
         Ok((
             ActionBinding {
                 action_id,
@@ -82,6 +120,8 @@ impl ActionBinding {
                 default_binds,
                 custom_binds: None,
                 activation_mode: action_level_mode,
+                option_group,
+                states,
             },
             bind_errors,
         ))
@@ -93,31 +133,117 @@ impl ActionBinding {
         get_translation(key, translations).to_string()
     }
 
-    /// Human summary of binds (keyboard | mouse). Returns `None` if both sides are empty.
-    pub fn get_binds_label(&self) -> Option<String> {
-        let binds = self.custom_binds.as_ref().unwrap_or(&self.default_binds);
-
-        let kb = binds
-            .keyboard
+    /// Localized label of the state called `name`, if the action has one.
+    pub fn get_state_label(
+        &self,
+        name: &str,
+        translations: &HashMap<String, String>,
+    ) -> Option<String> {
+        self.states
             .iter()
-            .filter(|b| !b.is_unbound)
-            .map(|b| b.to_string())
-            .collect::<Vec<_>>();
+            .find(|s| &*s.name == name)
+            .map(|s| s.get_label(translations))
+    }
 
-        let mouse = binds
-            .mouse
-            .iter()
-            .filter(|b| !b.is_unbound)
-            .map(|b| b.to_string())
-            .collect::<Vec<_>>();
+    /// Localized state labels joined with " / " (e.g. "Enable / Disable"). `None` for non-toggles.
+    pub fn get_states_label(&self, translations: &HashMap<String, String>) -> Option<String> {
+        if self.states.is_empty() {
+            return None;
+        }
+        Some(
+            self.states
+                .iter()
+                .map(|s| s.get_label(translations))
+                .collect::<Vec<_>>()
+                .join(" / "),
+        )
+    }
 
-        let mut parts = Vec::new();
-        if !kb.is_empty() {
-            parts.push(kb.join(", "));
+    /// Binds the game uses, merged per device: default < custom. A device the custom
+    /// profile touches takes its custom binds, the others keep their defaults, so an
+    /// explicit unbind clears its own device only. Unbind markers are left out.
+    pub fn effective_binds(&self) -> Binds {
+        let mut out = Binds::new();
+        for device in DeviceKind::ALL {
+            let (binds, _) = self.device_binds(device);
+            out.device_mut(device)
+                .extend(binds.iter().filter(|b| !b.is_unbound).cloned());
         }
-        if !mouse.is_empty() {
-            parts.push(mouse.join(", "));
+        out
+    }
+
+    /// The bind a Stream Deck press sends: the first executable keyboard bind in effect,
+    /// else the first mouse one.
+    pub fn executable_bind(&self) -> Option<Bind> {
+        let binds = self.effective_binds();
+        binds
+            .keyboard
+            .into_iter()
+            .chain(binds.mouse)
+            .find(|b| b.is_executable())
+    }
+
+    /// Binds the game uses on `device`: the custom ones if the custom profile touches that
+    /// device (an explicit unbind included), else the defaults. The flag is true for custom.
+    pub(crate) fn device_binds(&self, device: DeviceKind) -> (&[Bind], bool) {
+        match self
+            .custom_binds
+            .as_ref()
+            .map(|c| c.device(device))
+            .filter(|v| !v.is_empty())
+        {
+            Some(custom) => (custom, true),
+            None => (self.default_binds.device(device), false),
         }
+    }
+
+    /// Every bound input the game uses for this action (see `device_binds`), by device,
+    /// with where it comes from. Explicit unbinds are left out.
+    pub(crate) fn active_binds(&self) -> impl Iterator<Item = (DeviceKind, &Bind, BindSource)> {
+        DeviceKind::ALL.into_iter().flat_map(move |device| {
+            let (binds, custom) = self.device_binds(device);
+            binds
+                .iter()
+                .filter(|b| !b.is_unbound && b.main.is_some())
+                .map(move |b| {
+                    let source = match (custom, b.origin) {
+                        (false, _) => BindSource::Default,
+                        (true, BindOrigin::User) => BindSource::Custom,
+                        (true, BindOrigin::Generated) => BindSource::Generated,
+                    };
+                    (device, b, source)
+                })
+        })
+    }
+
+    /// Arena index of the activation mode `bind` runs with (its own, else the action's).
+    pub(crate) fn mode_of(&self, bind: &Bind) -> Option<usize> {
+        bind.activation_mode_idx.or(self.activation_mode)
+    }
+
+    /// Name of the activation mode `bind` runs with (its own, else the action's).
+    pub(crate) fn mode_name(&self, bind: &Bind, modes: &ActivationArena) -> Option<String> {
+        self.mode_of(bind)
+            .and_then(|ix| modes.get(ix))
+            .map(|m| m.display_name().into_owned())
+    }
+
+    /// Human summary of binds (keyboard | mouse | gamepad | joystick). Returns `None` if all devices are empty.
+    pub fn get_binds_label(&self) -> Option<String> {
+        let binds = self.effective_binds();
+
+        let parts = DeviceKind::ALL
+            .iter()
+            .map(|&d| {
+                binds
+                    .device(d)
+                    .iter()
+                    .map(|b| b.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|v| !v.is_empty())
+            .map(|v| v.join(", "))
+            .collect::<Vec<_>>();
 
         if parts.is_empty() {
             None
@@ -126,3 +252,68 @@ impl ActionBinding {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::bind::{BindMain, Key};
+
+    fn parse(xml: &str) -> ActionBinding {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let mut arena = ActivationArena::default();
+        let (binding, errors) =
+            ActionBinding::from_node(doc.root_element(), "spaceship_general", &mut arena).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        binding
+    }
+
+    fn custom(inputs: &[(&str, DeviceKind)]) -> Binds {
+        let mut binds = Binds::new();
+        for (input, device) in inputs {
+            binds
+                .device_mut(*device)
+                .push(Bind::from_device_string(input, *device, None).unwrap());
+        }
+        binds
+    }
+
+    fn mains(binds: &[Bind]) -> Vec<Option<BindMain>> {
+        binds.iter().map(|b| b.main.clone()).collect()
+    }
+
+    #[test]
+    fn default_wheel_bind_is_a_mouse_bind() {
+        let binding = parse(r#"<action name="v_zoom" keyboard="f" mouse="mwheel_up"/>"#);
+        assert_eq!(
+            mains(binding.default_binds.device(DeviceKind::Mouse)),
+            [Some(BindMain::MouseWheelUp)]
+        );
+        assert_eq!(binding.default_binds.device(DeviceKind::Keyboard).len(), 1);
+    }
+
+    #[test]
+    fn keyboard_rebind_keeps_default_wheel_bind() {
+        let mut binding = parse(r#"<action name="v_zoom" keyboard="f" mouse="mwheel_up"/>"#);
+        binding.custom_binds = Some(custom(&[("kb1_g", DeviceKind::Keyboard)]));
+
+        let effective = binding.effective_binds();
+        assert_eq!(
+            mains(effective.device(DeviceKind::Keyboard)),
+            [Some(BindMain::Key(Key::parse("g").unwrap()))]
+        );
+        assert_eq!(
+            mains(effective.device(DeviceKind::Mouse)),
+            [Some(BindMain::MouseWheelUp)]
+        );
+    }
+
+    #[test]
+    fn mouse_unbind_clears_default_wheel_bind() {
+        let mut binding = parse(r#"<action name="v_zoom" keyboard="f" mouse="mwheel_up"/>"#);
+        binding.custom_binds = Some(custom(&[("mo1_ ", DeviceKind::Mouse)]));
+
+        let effective = binding.effective_binds();
+        assert!(effective.device(DeviceKind::Mouse).is_empty());
+        assert_eq!(effective.device(DeviceKind::Keyboard).len(), 1);
+    }
+}
@@ -1,37 +1,86 @@
 use indexmap::IndexMap;
 use roxmltree::Document;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Arc,
+};
 
 use crate::bindings::{
-    action_map::ActionMap,
-    activation_mode::{ActivationArena, ActivationMode},
-    bind::Bind,
+    action_binding::ActionBinding,
+    action_group::{ACTION_GROUP_PREFIX, ActionGroup},
+    action_map::{ActionMap, ActionParseError},
+    activation_mode::{ACTIVATION_ATTRS, ActivationArena, ActivationMode},
+    bind::{Bind, BindOrigin, DeviceKind, Key, strip_device_prefix},
+    bindgen_config::BindGenConfig,
     binds::Binds,
-    binds_generator::BindGenerator,
+    conflicts::ConflictReport,
+    index::BindingsIndex,
+    load_report::{LoadIssueKind, LoadReport},
+    options::{OptionGroup, OptionTree},
+    passthrough::{ActionPassthrough, ProfilePassthrough, XmlElement, unknown_attrs},
+    profile_diff::ProfileDiff,
+    str_intern::intern,
 };
 use crate::core_log::CoreLog;
 
+/// Attributes on the profile root that `generate_mapping_xml` writes itself.
+const PROFILE_ROOT_ATTRS: &[&str] = &["version", "optionsVersion", "rebindVersion", "profileName"];
+
+/// `<rebind>` attributes the bind model understands (besides `ACTIVATION_ATTRS`).
+const REBIND_ATTRS: &[&str] = &["input", "activationMode", "device"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ActionBindings {
     pub action_maps: IndexMap<Arc<str>, ActionMap>,
     pub activation: ActivationArena,
+
+    /// `<actiongroup>`s keyed by group name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub action_groups: IndexMap<Arc<str>, ActionGroup>,
+
+    /// Modifier inputs declared by the default profile's `<modifiers>` block, as written
+    /// (e.g. "kb_lalt", "lshift", "gp_shoulderl").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<Arc<str>>,
+    /// `<modifiers>` declared by the custom profile (`None` if it had no such block).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_modifiers: Option<Vec<Arc<str>>>,
+
+    /// `<optiontree>` roots (one per device type) from the default profile.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<OptionTree>,
+
+    /// Action maps of the default profile left out by the `skip_actionmaps` given to
+    /// `load_default_profile`, in profile order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_maps: Vec<Arc<str>>,
+
+    /// Unmodeled content of the applied custom profile (written back by `generate_mapping_xml`).
+    #[serde(default, skip_serializing_if = "ProfilePassthrough::is_empty")]
+    pub passthrough: ProfilePassthrough,
 }
 
 impl ActionBindings {
     /// Load defaults from the game "defaultProfile.xml" and replace current data.
+    ///
+    /// Only an unreadable file is an error; elements that can't be used are dropped and
+    /// listed in the returned report.
     pub fn load_default_profile<P: AsRef<Path>>(
         &mut self,
         path: P,
         skip_actionmaps: &std::collections::HashSet<String>,
         actionmap_ui_categories: &HashMap<String, String>,
         logger: &Arc<dyn CoreLog>,
-    ) -> Result<(), String> {
+    ) -> Result<LoadReport, String> {
         let content =
             fs::read_to_string(&path).map_err(|e| format!("read default profile: {e}"))?;
         let doc = Document::parse(&content).map_err(|e| format!("parse default XML: {e}"))?;
 
         let mut ab = ActionBindings::default();
+        let mut report = LoadReport::new(path.as_ref());
 
         // ActivationMode nodes (dedupe by semantics+name)
         for node in doc
@@ -42,11 +91,31 @@ impl ActionBindings {
             let _ = ActivationMode::insert_or_get(&mut ab.activation, mode);
         }
 
+        // Declared modifier inputs (keyboard and controller)
+        ab.modifiers = read_modifiers(doc.root_element()).unwrap_or_default();
+
+        // Axis tuning trees (sensitivity, invert, curves)
+        for node in doc.descendants().filter(|n| n.has_tag_name("optiontree")) {
+            match OptionTree::from_node(node) {
+                Ok(tree) => ab.options.push(tree),
+                Err(e) => report.push(
+                    &doc,
+                    node.range().start,
+                    LoadIssueKind::BadOptionTree,
+                    None,
+                    format!("{e:?}"),
+                ),
+            }
+        }
+
         for node in doc.descendants().filter(|n| n.has_tag_name("actionmap")) {
             let Some(name) = node.attribute("name") else {
                 continue;
             };
             if skip_actionmaps.contains(name) {
+                if !ab.skipped_maps.iter().any(|m| &**m == name) {
+                    ab.skipped_maps.push(intern(name));
+                }
                 continue;
             }
 
@@ -54,16 +123,47 @@ impl ActionBindings {
                 Ok((amap, parse_errors)) => {
                     ab.action_maps.insert(amap.name.clone(), amap);
                     for e in parse_errors {
-                        logger.warn(&format!(
-                            "[load_default_profile] parse error in {name}: {e:?}"
-                        ));
+                        report_action_error(&mut report, &doc, node, name, e);
                     }
                 }
-                Err(e) => {
-                    logger.warn(&format!(
-                        "[load_default_profile] failed to parse {name}: {e:?}"
-                    ));
+                Err(e) => report.push(
+                    &doc,
+                    node.range().start,
+                    LoadIssueKind::BadActionMap,
+                    None,
+                    format!("{name}: {e:?}"),
+                ),
+            }
+        }
+
+        // Action groups reference actions by name, so resolve them once the maps are in.
+        for node in doc.descendants().filter(|n| n.has_tag_name("actiongroup")) {
+            match ActionGroup::from_node(node, &ab.action_maps) {
+                Ok(group) => {
+                    for member in &group.unresolved {
+                        let pos = node
+                            .children()
+                            .find(|n| n.attribute("name") == Some(&**member))
+                            .unwrap_or(node)
+                            .range()
+                            .start;
+                        report.push(
+                            &doc,
+                            pos,
+                            LoadIssueKind::UnknownGroupMember,
+                            Some(member.to_string()),
+                            format!("not an action (actiongroup {})", group.name),
+                        );
+                    }
+                    ab.action_groups.insert(group.name.clone(), group);
                 }
+                Err(e) => report.push(
+                    &doc,
+                    node.range().start,
+                    LoadIssueKind::BadActionGroup,
+                    None,
+                    format!("{e:?}"),
+                ),
             }
         }
 
@@ -77,82 +177,381 @@ impl ActionBindings {
 
         ab.activation.rebuild_indexes();
 
+        report.action_maps = ab.action_maps.len();
+        report.actions = total_actions;
+        report.activation_modes = ab.activation.len();
+
         self.action_maps = ab.action_maps;
         self.activation = ab.activation;
+        self.action_groups = ab.action_groups;
+        self.modifiers = ab.modifiers;
+        self.custom_modifiers = None;
+        self.options = ab.options;
+        self.skipped_maps = ab.skipped_maps;
+        self.passthrough = ProfilePassthrough::default();
 
-        Ok(())
+        Ok(report)
     }
 
     /// Overlay custom rebinds (user profile) onto the current graph.
+    ///
+    /// Rebinds that can't be applied are kept verbatim for re-emission and listed in the
+    /// returned report.
     pub fn apply_custom_profile<P: AsRef<Path>>(
         &mut self,
         path: P,
         logger: &Arc<dyn CoreLog>,
-    ) -> Result<(), String> {
+    ) -> Result<LoadReport, String> {
         let content = fs::read_to_string(&path).map_err(|e| format!("read custom profile: {e}"))?;
         let doc = Document::parse(&content).map_err(|e| format!("parse custom XML: {e}"))?;
+        let mut report = LoadReport::new(path.as_ref());
+        let mut maps_applied = HashSet::new();
+
+        // actionmaps.xml wraps everything in <ActionProfiles>; exported mappings don't.
+        let profile_node = doc
+            .descendants()
+            .find(|n| n.has_tag_name("ActionProfiles"))
+            .unwrap_or(doc.root_element());
+
+        let mut passthrough = ProfilePassthrough {
+            root_attrs: unknown_attrs(profile_node, PROFILE_ROOT_ATTRS),
+            elements: profile_node
+                .children()
+                .filter(|n| {
+                    n.is_element()
+                        && !n.has_tag_name("actionmap")
+                        && !n.has_tag_name("CustomisationUIHeader")
+                        && !n.has_tag_name("modifiers")
+                })
+                .map(XmlElement::from_node)
+                .collect(),
+            ..Default::default()
+        };
+
+        self.custom_modifiers = read_modifiers(profile_node);
 
         for am_node in doc.descendants().filter(|n| n.has_tag_name("actionmap")) {
             let Some(am_name) = am_node.attribute("name") else {
                 continue;
             };
 
+            let am_attrs = unknown_attrs(am_node, &["name"]);
+            if !am_attrs.is_empty() {
+                passthrough
+                    .actionmap_attrs
+                    .insert(am_name.to_string(), am_attrs);
+            }
+
             for act_node in am_node.children().filter(|n| n.has_tag_name("action")) {
                 let Some(act_name) = act_node.attribute("name") else {
                     continue;
                 };
 
+                // Actions we don't load (skipped maps, removed actions) are kept verbatim.
+                let known = self
+                    .action_maps
+                    .get(am_name)
+                    .is_some_and(|m| m.actions.contains_key(act_name));
+                if !known {
+                    // Maps left out of the defaults on purpose aren't worth a mention
+                    if !self.skipped_maps.iter().any(|m| &**m == am_name) {
+                        report.push(
+                            &doc,
+                            act_node.range().start,
+                            LoadIssueKind::UnknownAction,
+                            Some(format!("{am_name}.{act_name}")),
+                            "not in the default profile; kept as is".to_string(),
+                        );
+                    }
+                    passthrough
+                        .unknown_actions
+                        .entry(am_name.to_string())
+                        .or_default()
+                        .push(XmlElement::from_node(act_node));
+                    continue;
+                }
+
                 let mut binds = Binds::new();
+                let mut extras = ActionPassthrough {
+                    attrs: unknown_attrs(act_node, &["name"]),
+                    children: act_node
+                        .children()
+                        .filter(|n| n.is_element() && !n.has_tag_name("rebind"))
+                        .map(XmlElement::from_node)
+                        .collect(),
+                };
 
                 for rebind in act_node.children().filter(|n| n.has_tag_name("rebind")) {
                     let input = rebind.attribute("input").unwrap_or("").trim();
-                    // Expect prefixes like kb1_, mo1_...
-                    let (prefix, key_str) = match input.get(..3).zip(input.get(3..)) {
-                        Some((p, rest)) => (p, rest.trim()),
-                        None => {
-                            logger.warn(&format!(
-                                "[apply_custom_profile] bad input '{input}' on {am_name}.{act_name}"
-                            ));
-                            continue;
-                        }
+                    // Expect prefixes like kb1_, mo1_, gp1_, js1_...
+                    let Some(device) = input.get(..2).and_then(DeviceKind::from_prefix) else {
+                        report.push(
+                            &doc,
+                            rebind.range().start,
+                            LoadIssueKind::UnknownDevice,
+                            Some(format!("{am_name}.{act_name}")),
+                            format!("input '{input}'"),
+                        );
+                        extras.children.push(XmlElement::from_node(rebind));
+                        continue;
                     };
 
-                    let am_ix = rebind
-                        .attribute("activationMode")
-                        .and_then(|name| self.activation.find_by_name(name));
-
-                    match Bind::from_string(key_str, am_ix) {
-                        Ok(b) => match prefix {
-                            "kb1" => binds.keyboard.push(b),
-                            "mo1" => binds.mouse.push(b),
-                            _ => logger.debug(&format!(
-                                "[apply_custom_profile] ignoring device '{prefix}' on {am_name}.{act_name}"
-                            )),
-                        },
-                        Err(e) => logger.warn(&format!(
-                            "[apply_custom_profile] parse bind {am_name}.{act_name}: {e:?}"
-                        )),
+                    // Named mode, or inline activation attributes on the rebind itself
+                    let am_ix = ActivationMode::resolve(rebind, None, &mut self.activation);
+
+                    match Bind::from_device_string(input, device, am_ix) {
+                        Ok(mut b) => {
+                            b.extra_attrs = unknown_attrs(rebind, REBIND_ATTRS)
+                                .into_iter()
+                                .filter(|(k, _)| !ACTIVATION_ATTRS.contains(&k.as_str()))
+                                .collect();
+                            if am_ix.is_none() {
+                                // Unknown mode name: keep it verbatim rather than drop it
+                                if let Some(name) = rebind.attribute("activationMode") {
+                                    report.push(
+                                        &doc,
+                                        rebind.range().start,
+                                        LoadIssueKind::UnknownActivationMode,
+                                        Some(format!("{am_name}.{act_name}")),
+                                        format!("activationMode '{name}' on '{input}'"),
+                                    );
+                                    b.extra_attrs
+                                        .push(("activationMode".to_string(), name.to_string()));
+                                }
+                            }
+                            binds.device_mut(device).push(b);
+                            report.rebinds += 1;
+                        }
+                        Err(e) => {
+                            report.push(
+                                &doc,
+                                rebind.range().start,
+                                LoadIssueKind::BadBind,
+                                Some(format!("{am_name}.{act_name}")),
+                                format!("input '{input}': {e:?}"),
+                            );
+                            extras.children.push(XmlElement::from_node(rebind));
+                        }
                     }
                 }
 
-                if let Some(amap) = self.action_maps.get_mut(am_name) {
-                    if let Some(abind) = amap.actions.get_mut(act_name) {
-                        abind.custom_binds = Some(binds);
-                    }
+                if !extras.is_empty() {
+                    passthrough
+                        .actions
+                        .insert(format!("{am_name}.{act_name}"), extras);
+                }
+
+                if let Some(abind) = self
+                    .action_maps
+                    .get_mut(am_name)
+                    .and_then(|m| m.actions.get_mut(act_name))
+                {
+                    abind.custom_binds = Some(binds);
+                    report.actions += 1;
+                    maps_applied.insert(am_name);
                 }
             }
         }
 
-        logger.info("[apply_custom_profile] Finished applying custom rebinds");
-        Ok(())
+        let kept = passthrough.elements.len()
+            + passthrough
+                .unknown_actions
+                .values()
+                .map(Vec::len)
+                .sum::<usize>();
+        logger.debug(&format!(
+            "[apply_custom_profile] kept {kept} unrecognized elements for re-emission"
+        ));
+        self.passthrough = passthrough;
+
+        report.action_maps = maps_applied.len();
+        report.activation_modes = self.activation.len();
+        logger.info(&format!(
+            "[apply_custom_profile] Applied {} rebinds to {} actions",
+            report.rebinds, report.actions
+        ));
+        Ok(report)
+    }
+
+    /// Action by id (`map.action`).
+    pub fn get_action(&self, id: &str) -> Option<&ActionBinding> {
+        let (map, action) = id.split_once('.')?;
+        self.action_maps
+            .get(map)
+            .and_then(|m| m.actions.get(action))
+    }
+
+    /// Actions behind a target id: a single action id, or `group:<name>` for all members
+    /// of an action group. Unknown ids resolve to nothing.
+    pub fn resolve_actions(&self, id: &str) -> Vec<&ActionBinding> {
+        match id.strip_prefix(ACTION_GROUP_PREFIX) {
+            Some(group) => self
+                .action_groups
+                .get(group)
+                .into_iter()
+                .flat_map(|g| &g.members)
+                .filter_map(|m| self.get_action(m))
+                .collect(),
+            None => self.get_action(id).into_iter().collect(),
+        }
+    }
+
+    /// Declared modifier inputs: the defaults, then the user's additions (deduped by
+    /// device + input, so "kb_lalt" and "kb1_lalt" are the same modifier).
+    pub fn declared_modifiers(&self) -> Vec<Arc<str>> {
+        let mut seen = HashSet::new();
+        self.modifiers
+            .iter()
+            .chain(self.custom_modifiers.iter().flatten())
+            .filter(|m| seen.insert(modifier_identity(m)))
+            .cloned()
+            .collect()
+    }
+
+    /// Keyboard keys among the declared modifiers (the generator's modifier pool).
+    pub fn modifier_keys(&self) -> HashSet<Key> {
+        self.declared_modifiers()
+            .iter()
+            .filter_map(|m| match modifier_identity(m) {
+                (DeviceKind::Keyboard, rest) => Key::parse(rest),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Option tree for a device type (mouse axes live in the keyboard tree).
+    pub fn option_tree(&self, device: DeviceKind) -> Option<&OptionTree> {
+        let device = match device {
+            DeviceKind::Mouse => DeviceKind::Keyboard,
+            d => d,
+        };
+        self.options.iter().find(|t| t.device == device)
+    }
+
+    /// The action's `optionGroup` resolved in every tree that has it.
+    pub fn option_groups<'a>(
+        &'a self,
+        action: &ActionBinding,
+    ) -> Vec<(DeviceKind, &'a OptionGroup)> {
+        let Some(name) = action.option_group.as_deref() else {
+            return Vec::new();
+        };
+        self.options
+            .iter()
+            .filter_map(|t| t.find(name).map(|g| (t.device, g)))
+            .collect()
+    }
+
+    /// Mutable access to a group for editing axis tuning. The group is marked edited, so
+    /// `generate_mapping_xml` writes its values into the profile's `<options>`.
+    pub fn option_group_mut(&mut self, device: DeviceKind, name: &str) -> Option<&mut OptionGroup> {
+        let device = match device {
+            DeviceKind::Mouse => DeviceKind::Keyboard,
+            d => d,
+        };
+        let group = self
+            .options
+            .iter_mut()
+            .find(|t| t.device == device)
+            .and_then(|t| t.find_mut(name))?;
+        group.edited = true;
+        Some(group)
     }
 
     /// Generate missing binds via the generator (fills gaps in-place).
     pub fn generate_missing_binds(&mut self, logger: &Arc<dyn CoreLog>) {
-        let mut bind_gen = BindGenerator::with_logger(&self.activation, logger);
+        self.generate_missing_binds_with(&BindGenConfig::default(), None, logger);
+    }
+
+    /// Like `generate_missing_binds`, but actions keep the bind `previous` generated for
+    /// them where it is still free, so regenerating doesn't reshuffle existing binds.
+    pub fn regenerate_missing_binds(
+        &mut self,
+        previous: &ActionBindings,
+        logger: &Arc<dyn CoreLog>,
+    ) {
+        self.generate_missing_binds_with(&BindGenConfig::default(), Some(previous), logger);
+    }
+
+    /// Generate missing binds with `config` applied over the built-in rules, optionally
+    /// keeping the binds `previous` generated. The CLI and the plugin both go through
+    /// here, so the same config and inputs give the same binds.
+    pub fn generate_missing_binds_with(
+        &mut self,
+        config: &BindGenConfig,
+        previous: Option<&ActionBindings>,
+        logger: &Arc<dyn CoreLog>,
+    ) {
+        let mut bind_gen = config.build(&self.activation, logger);
+        bind_gen.action_groups = self.action_groups.values().cloned().collect();
+        // Declared modifiers are the pool; the built-in set only if the profile declares
+        // none. An explicit `candidate_modifiers` in the config still wins.
+        let declared = self.modifier_keys();
+        if !declared.is_empty() && config.candidate_modifiers.is_none() {
+            bind_gen.available_modifiers = declared.clone();
+        }
+        // A key declared as a modifier (e.g. "kb1_f") can't also be a main key
+        bind_gen.available_keys.retain(|k| !declared.contains(k));
+        if let Some(previous) = previous {
+            bind_gen.previous_binds = previous.generated_binds();
+        }
+        // Priority targets may name action groups
+        bind_gen.priority_actions.clear();
+        for id in config.priority_actions.iter().flatten() {
+            let actions = self.resolve_actions(id);
+            if actions.is_empty() {
+                logger.warn(&format!(
+                    "[generate_missing_binds] unknown priority action '{id}'"
+                ));
+            }
+            bind_gen
+                .priority_actions
+                .extend(actions.into_iter().map(|a| a.action_id.clone()));
+        }
         bind_gen.generate_missing_binds(&mut self.action_maps);
     }
 
+    /// Generated keyboard/mouse binds by action id (first per action), e.g. from a cached
+    /// previous run. Activation modes are dropped; they index this graph's arena.
+    pub fn generated_binds(&self) -> HashMap<Arc<str>, Bind> {
+        self.action_maps
+            .values()
+            .flat_map(|m| m.actions.values())
+            .filter_map(|a| {
+                let custom = a.custom_binds.as_ref()?;
+                let bind = custom
+                    .keyboard
+                    .iter()
+                    .chain(&custom.mouse)
+                    .find(|b| b.origin == BindOrigin::Generated && !b.is_unbound)?;
+                let mut bind = bind.clone();
+                bind.activation_mode_idx = None;
+                Some((a.action_id.clone(), bind))
+            })
+            .collect()
+    }
+
+    /// Binds shared by actions in overlapping categories, grouped like the generator does
+    /// by default (`CATEGORY_GROUPS`).
+    pub fn conflicts(&self) -> ConflictReport {
+        self.conflicts_with(&BindGenConfig::default())
+    }
+
+    /// Like `conflicts`, with the category groups of `config`.
+    pub fn conflicts_with(&self, config: &BindGenConfig) -> ConflictReport {
+        ConflictReport::analyze(self, &config.category_group_map())
+    }
+
+    /// Actions whose binds differ in `other` (see `ProfileDiff`).
+    pub fn diff(&self, other: &ActionBindings) -> ProfileDiff {
+        ProfileDiff::between(self, other)
+    }
+
+    /// Index this snapshot for reverse lookups (see `BindingsIndex`).
+    pub fn into_index(self) -> BindingsIndex {
+        BindingsIndex::new(Arc::new(self))
+    }
+
     pub fn to_json(&self) -> Result<String, String> {
         serde_json::to_string_pretty(&self).map_err(|e| format!("serialize ActionBindings: {e}"))
     }
@@ -169,3 +568,68 @@ impl ActionBindings {
         Ok(data)
     }
 }
+
+/// `<mod input="..."/>` entries of the first `<modifiers>` child of `parent`.
+fn read_modifiers(parent: roxmltree::Node) -> Option<Vec<Arc<str>>> {
+    let block = parent.children().find(|n| n.has_tag_name("modifiers"))?;
+    Some(
+        block
+            .children()
+            .filter(|n| n.has_tag_name("mod"))
+            .filter_map(|n| n.attribute("input"))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(intern)
+            .collect(),
+    )
+}
+
+/// Add an `ActionMap::from_node` error to `report`, positioned at its `<action>` (or at
+/// the map when the action can't be found again).
+fn report_action_error(
+    report: &mut LoadReport,
+    doc: &Document,
+    map_node: roxmltree::Node,
+    map_name: &str,
+    error: ActionParseError,
+) {
+    match error {
+        ActionParseError::ActionBindingError {
+            action_name,
+            range,
+            error,
+        } => report.push(
+            doc,
+            range.start,
+            LoadIssueKind::BadAction,
+            action_name.map(|a| format!("{map_name}.{a}")),
+            format!("{error:?}"),
+        ),
+        ActionParseError::BindError {
+            action_name,
+            bind_error,
+        } => {
+            let pos = map_node
+                .children()
+                .find(|n| n.has_tag_name("action") && n.attribute("name") == Some(&action_name))
+                .unwrap_or(map_node)
+                .range()
+                .start;
+            report.push(
+                doc,
+                pos,
+                LoadIssueKind::BadBind,
+                Some(format!("{map_name}.{action_name}")),
+                format!("{bind_error:?}"),
+            );
+        }
+    }
+}
+
+/// Device + input of a declared modifier, ignoring the instance ("kb1_lalt" → keyboard, "lalt").
+fn modifier_identity(input: &str) -> (DeviceKind, &str) {
+    match strip_device_prefix(input) {
+        (Some(kind), _, rest) => (kind, rest),
+        (None, _, rest) => (DeviceKind::Keyboard, rest),
+    }
+}
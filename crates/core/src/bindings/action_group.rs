@@ -0,0 +1,74 @@
+//! `<actiongroup>` definitions: one named group driving several actions.
+//!
+//! Members are listed by action name only; they're resolved to action ids
+//! (`map.action`) against the loaded action maps.
+
+use indexmap::IndexMap;
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::bindings::{action_map::ActionMap, str_intern::intern};
+
+/// Prefix that marks a group in places that take an action id (e.g. ScAction settings).
+pub const ACTION_GROUP_PREFIX: &str = "group:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionGroup {
+    /// Group name (`<actiongroup action="...">`).
+    pub name: Arc<str>,
+    /// Resolved member action ids, in source order.
+    pub members: Vec<Arc<str>>,
+    /// Member names with no loaded action (skipped maps, renamed actions).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<Arc<str>>,
+}
+
+#[derive(Debug)]
+pub enum ActionGroupParseError {
+    MissingName,
+}
+
+impl ActionGroup {
+    /// Parse an `<actiongroup>` node and resolve its members against `action_maps`.
+    ///
+    /// A member name found in several maps resolves to all of them.
+    pub fn from_node(
+        node: Node,
+        action_maps: &IndexMap<Arc<str>, ActionMap>,
+    ) -> Result<Self, ActionGroupParseError> {
+        let name = node
+            .attribute("action")
+            .ok_or(ActionGroupParseError::MissingName)?;
+
+        let mut members = Vec::new();
+        let mut unresolved = Vec::new();
+        for member in node
+            .children()
+            .filter(|n| n.has_tag_name("action"))
+            .filter_map(|n| n.attribute("name"))
+        {
+            let before = members.len();
+            members.extend(
+                action_maps
+                    .values()
+                    .filter_map(|m| m.actions.get(member))
+                    .map(|a| a.action_id.clone()),
+            );
+            if members.len() == before {
+                unresolved.push(intern(member));
+            }
+        }
+
+        Ok(ActionGroup {
+            name: intern(name),
+            members,
+            unresolved,
+        })
+    }
+
+    /// Id to store in settings that accept either an action id or a group.
+    pub fn target_id(&self) -> String {
+        format!("{ACTION_GROUP_PREFIX}{}", self.name)
+    }
+}
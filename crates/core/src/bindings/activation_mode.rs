@@ -2,14 +2,36 @@
 //!
 //! - `ActivationMode::resolve` finds/creates an index for the most specific mode
 //!   described at a node, optionally falling back to a parent node.
-//! - `ActivationArena` dedupes by `name` first (if present), then by a semantic key
-//!   that quantizes float timings to milliseconds.
+//! - `ActivationArena` dedupes named modes by `name` and unnamed (inline) modes by a
+//!   semantic key that quantizes float timings to milliseconds. Unnamed modes get a
+//!   stable synthesized name (`ActivationMode::display_name`) for lookups and caches.
+//! - Thresholds/delays keep SC's `-1` ("disabled") distinct from an unset attribute;
+//!   use `ActivationMode::active` when only a usable value matters.
 //!
 //! This file is pure core: no plugin deps.
 
 use roxmltree::Node;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
+
+/// Attributes that describe an activation mode inline (on `<ActivationMode>`, `<action>`,
+/// device nodes or `<rebind>`).
+pub const ACTIVATION_ATTRS: &[&str] = &[
+    "onPress",
+    "onHold",
+    "onRelease",
+    "multiTap",
+    "multiTapBlock",
+    "pressTriggerThreshold",
+    "releaseTriggerThreshold",
+    "releaseTriggerDelay",
+    "retriggerable",
+    "holdTriggerDelay",
+    "holdRepeatDelay",
+];
+
+/// Prefix of synthesized names for unnamed modes.
+const INLINE_PREFIX: &str = "inline_";
 
 /// Parsed activation behavior for an input.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -23,7 +45,7 @@ pub struct ActivationMode {
     pub multi_tap: i64,
     /// If true, block input while multi-tapping is being considered.
     pub multi_tap_block: bool,
-    /// Threshold to consider a press as "triggered".
+    /// Threshold to consider a press as "triggered" (`Some(-1.0)` = disabled, as in SC XML).
     pub press_trigger_threshold: Option<f32>,
     /// Threshold to consider a release as "triggered".
     pub release_trigger_threshold: Option<f32>,
@@ -37,6 +59,21 @@ pub struct ActivationMode {
     pub hold_repeat_delay: Option<f32>,
 }
 
+/// When a mode fires within one gesture on its input, which decides whether two modes
+/// can share that input (see `ActivationMode::can_share_with`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerKind {
+    /// On the way down, or for as long as the input is held (press, hold, all, toggles).
+    Immediate,
+    /// On a release within `max_ms` (tap, tap_quicker).
+    Tap { max_ms: i32 },
+    /// Once the input has been held for `min_ms` (delayed_press, delayed_hold, ...).
+    Held { min_ms: i32 },
+    /// On the `taps`-th press in a row (double_tap). `block` holds the earlier presses
+    /// back from other modes on the input (`multiTapBlock`).
+    MultiTap { taps: i64, block: bool },
+}
+
 impl ActivationMode {
     /// Parse attributes off `node`. If `include_name` is true, captures `name=""` as well.
     pub fn from_node(node: Node, include_name: bool) -> Self {
@@ -48,11 +85,12 @@ impl ActivationMode {
         fn bool_attr(node: Node, k: &str) -> bool {
             attr(node, k) == Some("1")
         }
+        /// Keeps the `-1` sentinel; `None` only when the attribute is absent/invalid.
         #[inline]
-        fn f32_attr_nonneg(node: Node, k: &str) -> Option<f32> {
+        fn f32_attr(node: Node, k: &str) -> Option<f32> {
             attr(node, k)
-                .and_then(|v| v.parse::<f32>().ok())
-                .filter(|&v| v.is_finite() && v >= 0.0)
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .filter(|&v| v.is_finite())
         }
         #[inline]
         fn i64_attr_nonneg(node: Node, k: &str) -> Option<i64> {
@@ -72,31 +110,134 @@ impl ActivationMode {
             on_release: bool_attr(node, "onRelease"),
             multi_tap: i64_attr_nonneg(node, "multiTap").unwrap_or(1),
             multi_tap_block: bool_attr(node, "multiTapBlock"),
-            press_trigger_threshold: f32_attr_nonneg(node, "pressTriggerThreshold"),
-            release_trigger_threshold: f32_attr_nonneg(node, "releaseTriggerThreshold"),
-            release_trigger_delay: f32_attr_nonneg(node, "releaseTriggerDelay"),
+            press_trigger_threshold: f32_attr(node, "pressTriggerThreshold"),
+            release_trigger_threshold: f32_attr(node, "releaseTriggerThreshold"),
+            release_trigger_delay: f32_attr(node, "releaseTriggerDelay"),
             retriggerable: bool_attr(node, "retriggerable"),
-            hold_trigger_delay: f32_attr_nonneg(node, "holdTriggerDelay"),
-            hold_repeat_delay: f32_attr_nonneg(node, "holdRepeatDelay"),
+            hold_trigger_delay: f32_attr(node, "holdTriggerDelay"),
+            hold_repeat_delay: f32_attr(node, "holdRepeatDelay"),
         }
     }
 
     /// Heuristic: does this node carry any activation-mode attributes?
     pub fn has_valid_attributes(node: Node) -> bool {
-        const KEYS: &[&str] = &[
-            "onPress",
-            "onHold",
-            "onRelease",
-            "multiTap",
-            "multiTapBlock",
-            "pressTriggerThreshold",
-            "releaseTriggerThreshold",
-            "releaseTriggerDelay",
-            "retriggerable",
-            "holdTriggerDelay",
-            "holdRepeatDelay",
+        ACTIVATION_ATTRS
+            .iter()
+            .any(|&k| node.attribute(k).is_some())
+    }
+
+    /// A threshold/delay if it is set and not the `-1` "disabled" sentinel.
+    #[inline]
+    pub fn active(v: Option<f32>) -> Option<f32> {
+        v.filter(|&v| v >= 0.0)
+    }
+
+    /// The real name, or a stable synthesized one for unnamed (inline) modes,
+    /// e.g. "inline_p1h0r0_mt2b1_ptt-1_rtt-1_rtd0_rt0".
+    pub fn display_name(&self) -> Cow<'_, str> {
+        match self.name.as_deref() {
+            Some(n) => Cow::Borrowed(n),
+            None => Cow::Owned(self.synthesized_name()),
+        }
+    }
+
+    fn synthesized_name(&self) -> String {
+        let k = ModeKey::from(self);
+        let ms = |v: Option<i32>| v.map_or_else(|| "u".to_string(), |v| v.to_string());
+        let mut out = format!(
+            "{INLINE_PREFIX}p{}h{}r{}_mt{}b{}_ptt{}_rtt{}_rtd{}_rt{}",
+            k.on_press as u8,
+            k.on_hold as u8,
+            k.on_release as u8,
+            k.multi_tap,
+            k.multi_tap_block as u8,
+            ms(k.press_ms),
+            ms(k.release_thr_ms),
+            ms(k.release_delay_ms),
+            k.retriggerable as u8,
+        );
+        // Rare attributes only when present, so common names stay short
+        if k.hold_ms.is_some() {
+            out.push_str(&format!("_htd{}", ms(k.hold_ms)));
+        }
+        if k.hold_repeat_ms.is_some() {
+            out.push_str(&format!("_hrd{}", ms(k.hold_repeat_ms)));
+        }
+        out
+    }
+
+    /// Inline XML attributes describing this mode (for writing unnamed modes on `<rebind>`).
+    /// Unset thresholds/delays are omitted; `-1` is written as `-1`.
+    pub fn to_attrs(&self) -> Vec<(&'static str, String)> {
+        let flag = |b: bool| if b { "1" } else { "0" }.to_string();
+        let mut out = vec![
+            ("onPress", flag(self.on_press)),
+            ("onHold", flag(self.on_hold)),
+            ("onRelease", flag(self.on_release)),
+            ("multiTap", self.multi_tap.to_string()),
+            ("multiTapBlock", flag(self.multi_tap_block)),
         ];
-        KEYS.iter().any(|&k| node.attribute(k).is_some())
+        let timings = [
+            ("pressTriggerThreshold", self.press_trigger_threshold),
+            ("releaseTriggerThreshold", self.release_trigger_threshold),
+            ("releaseTriggerDelay", self.release_trigger_delay),
+        ];
+        out.extend(
+            timings
+                .into_iter()
+                .filter_map(|(k, v)| Some((k, v?.to_string()))),
+        );
+        out.push(("retriggerable", flag(self.retriggerable)));
+        let holds = [
+            ("holdTriggerDelay", self.hold_trigger_delay),
+            ("holdRepeatDelay", self.hold_repeat_delay),
+        ];
+        out.extend(
+            holds
+                .into_iter()
+                .filter_map(|(k, v)| Some((k, v?.to_string()))),
+        );
+        out
+    }
+
+    /// How this mode fires (see `TriggerKind`).
+    pub fn trigger_kind(&self) -> TriggerKind {
+        let ms = |v: Option<f32>| {
+            Self::active(v)
+                .filter(|&v| v > 0.0)
+                .map(|v| (v * 1000.0).round() as i32)
+        };
+        if self.multi_tap > 1 {
+            return TriggerKind::MultiTap {
+                taps: self.multi_tap,
+                block: self.multi_tap_block,
+            };
+        }
+        if let Some(min_ms) = ms(self.press_trigger_threshold) {
+            return TriggerKind::Held { min_ms };
+        }
+        let release_only = self.on_release && !self.on_press && !self.on_hold;
+        if let Some(max_ms) = ms(self.release_trigger_threshold).filter(|_| release_only) {
+            return TriggerKind::Tap { max_ms };
+        }
+        TriggerKind::Immediate
+    }
+
+    /// Can an input carry both modes without one gesture firing both? A tap shares with
+    /// a hold that only starts after the tap window, blocking multi-taps share with
+    /// anything but the same tap count (a non-blocking one lets its first press through),
+    /// and immediate modes share with nothing.
+    pub fn can_share_with(&self, other: &ActivationMode) -> bool {
+        use TriggerKind::*;
+        match (self.trigger_kind(), other.trigger_kind()) {
+            (Immediate, _) | (_, Immediate) => false,
+            (Tap { max_ms }, Held { min_ms }) | (Held { min_ms }, Tap { max_ms }) => {
+                max_ms <= min_ms
+            }
+            (MultiTap { taps: a, block: x }, MultiTap { taps: b, block: y }) => a != b && x && y,
+            (MultiTap { block, .. }, _) | (_, MultiTap { block, .. }) => block,
+            (Tap { .. }, Tap { .. }) | (Held { .. }, Held { .. }) => false,
+        }
     }
 
     /// Resolve a mode for `node` (and optional `fallback` node) into the arena.
@@ -156,6 +297,7 @@ impl ActivationMode {
 }
 
 /// Quantized semantic key for dedupe (convert seconds → milliseconds, round to nearest).
+/// `-1` stays `-1000`, so it never collides with an unset (`None`) or zero value.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct ModeKey {
     on_press: bool,
@@ -163,21 +305,21 @@ struct ModeKey {
     on_release: bool,
     multi_tap: i64,
     multi_tap_block: bool,
-    press_ms: Option<u32>,
-    release_thr_ms: Option<u32>,
-    release_delay_ms: Option<u32>,
+    press_ms: Option<i32>,
+    release_thr_ms: Option<i32>,
+    release_delay_ms: Option<i32>,
     retriggerable: bool,
-    hold_ms: Option<u32>,
-    hold_repeat_ms: Option<u32>,
+    hold_ms: Option<i32>,
+    hold_repeat_ms: Option<i32>,
 }
 
 impl ModeKey {
     #[inline]
-    fn quantize_ms(x: Option<f32>) -> Option<u32> {
+    fn quantize_ms(x: Option<f32>) -> Option<i32> {
         x.map(|v| {
             // robust to weird inputs
             let v = if v.is_finite() { v } else { 0.0 };
-            (v * 1000.0).round().max(0.0) as u32
+            (v * 1000.0).round() as i32
         })
     }
 
@@ -235,6 +377,16 @@ impl ActivationArena {
         self.modes.iter().enumerate()
     }
 
+    /// `ActivationMode::can_share_with` by arena index. A missing mode behaves like
+    /// "press" (the game's default), so it shares with nothing.
+    pub fn can_share(&self, a: Option<usize>, b: Option<usize>) -> bool {
+        match (a.and_then(|ix| self.get(ix)), b.and_then(|ix| self.get(ix))) {
+            (Some(a), Some(b)) => a.can_share_with(b),
+            _ => false,
+        }
+    }
+
+    /// Index of a mode by real name or by synthesized name (`ActivationMode::display_name`).
     #[inline]
     pub fn find_by_name(&self, name: &str) -> Option<usize> {
         self.name_to_index.get(name).copied()
@@ -242,43 +394,105 @@ impl ActivationArena {
 
     /// Insert or return an existing index for a mode.
     ///
-    /// Dedupe order:
-    /// 1) If `name` exists, it wins and maps to a single index.
-    /// 2) Else, dedupe by semantic key (`ModeKey`).
+    /// Dedupe rules:
+    /// 1) Named modes are identified by `name` only (two names never merge, so a bind
+    ///    keeps the exact name it was written with).
+    /// 2) Unnamed modes dedupe by semantic key (`ModeKey`) among unnamed modes, and are
+    ///    also findable by their synthesized name.
     pub fn insert_or_get_mode(&mut self, m: ActivationMode) -> usize {
         if let Some(name) = m.name.as_deref() {
             if let Some(ix) = self.name_to_index.get(name) {
                 return *ix;
             }
-        }
-
-        let key = ModeKey::from(&m);
-        if let Some(ix) = self.by_key.get(&key) {
-            // If the existing semantic entry didn’t have a name and this one does, remember it.
-            if let Some(name) = m.name {
-                self.name_to_index.entry(name).or_insert(*ix);
-            }
+        } else if let Some(ix) = self.by_key.get(&ModeKey::from(&m)) {
             return *ix;
         }
 
         let ix = self.modes.len();
-        if let Some(name) = m.name.as_deref() {
-            self.name_to_index.insert(name.to_string(), ix);
-        }
-        self.by_key.insert(key, ix);
+        self.index_mode(ix, &m);
         self.modes.push(m);
         ix
     }
 
+    fn index_mode(&mut self, ix: usize, m: &ActivationMode) {
+        match m.name.as_deref() {
+            Some(name) => {
+                self.name_to_index.insert(name.to_string(), ix);
+            }
+            None => {
+                self.by_key.insert(ModeKey::from(m), ix);
+                self.name_to_index.insert(m.synthesized_name(), ix);
+            }
+        }
+    }
+
     /// Rebuild lookup maps (call after deserialization or bulk edits).
     pub fn rebuild_indexes(&mut self) {
         self.name_to_index.clear();
         self.by_key.clear();
-        for (ix, m) in self.modes.iter().enumerate() {
-            if let Some(name) = m.name.as_deref() {
-                self.name_to_index.insert(name.to_string(), ix);
-            }
-            self.by_key.insert(ModeKey::from(m), ix);
+        let modes = std::mem::take(&mut self.modes);
+        for (ix, m) in modes.iter().enumerate() {
+            self.index_mode(ix, m);
         }
+        self.modes = modes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Modes as `defaultProfile.xml` defines them.
+    fn mode(attrs: &str) -> ActivationMode {
+        let xml = format!(r#"<ActivationMode {attrs} />"#);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        ActivationMode::from_node(doc.root_element(), true)
+    }
+
+    fn press() -> ActivationMode {
+        mode(
+            r#"name="press" onPress="1" onHold="0" onRelease="0" multiTap="1" multiTapBlock="1" pressTriggerThreshold="-1" releaseTriggerThreshold="-1" releaseTriggerDelay="0" retriggerable="0""#,
+        )
+    }
+
+    fn tap() -> ActivationMode {
+        mode(
+            r#"name="tap" onPress="0" onHold="0" onRelease="1" multiTap="1" multiTapBlock="1" pressTriggerThreshold="-1" releaseTriggerThreshold="0.25" releaseTriggerDelay="0" retriggerable="0""#,
+        )
+    }
+
+    fn delayed_press() -> ActivationMode {
+        mode(
+            r#"name="delayed_press" onPress="0" onHold="1" onRelease="0" multiTap="1" multiTapBlock="1" pressTriggerThreshold="0.25" releaseTriggerThreshold="-1" releaseTriggerDelay="0" retriggerable="0""#,
+        )
+    }
+
+    fn double_tap(block: bool) -> ActivationMode {
+        mode(&format!(
+            r#"name="double_tap" onPress="1" onHold="0" onRelease="0" multiTap="2" multiTapBlock="{}" pressTriggerThreshold="-1" releaseTriggerThreshold="-1" releaseTriggerDelay="0" retriggerable="0""#,
+            block as u8
+        ))
+    }
+
+    #[test]
+    fn tap_shares_with_a_later_hold() {
+        assert!(tap().can_share_with(&delayed_press()));
+        assert!(delayed_press().can_share_with(&tap()));
+        assert!(!press().can_share_with(&delayed_press()));
+    }
+
+    #[test]
+    fn blocking_double_tap_shares_with_a_tap() {
+        assert!(double_tap(true).can_share_with(&tap()));
+        assert!(tap().can_share_with(&double_tap(true)));
+        assert!(!double_tap(true).can_share_with(&double_tap(true)));
+    }
+
+    #[test]
+    fn non_blocking_double_tap_shares_with_nothing() {
+        assert!(!double_tap(false).can_share_with(&tap()));
+        assert!(!tap().can_share_with(&double_tap(false)));
+        assert!(!double_tap(false).can_share_with(&delayed_press()));
+        assert!(!double_tap(false).can_share_with(&press()));
     }
 }
@@ -0,0 +1,260 @@
+//! Minimum-cost assignment of rows (actions) to distinct columns (candidate binds).
+
+use std::collections::BTreeSet;
+
+/// Assign each row a distinct column so the summed cost is minimal (Hungarian method,
+/// O(rows² · cols)). `None` cells are forbidden; rows that can't get an allowed column
+/// (more rows than usable columns) come back as `None`.
+///
+/// Deterministic: ties are broken by row and column order.
+pub(crate) fn min_cost_assignment(costs: &[Vec<Option<f64>>]) -> Vec<Option<usize>> {
+    // Rows with no allowed column stay unassigned and don't take part.
+    let rows: Vec<usize> = (0..costs.len())
+        .filter(|&i| costs[i].iter().any(Option::is_some))
+        .collect();
+    let n = rows.len();
+    let mut out = vec![None; costs.len()];
+    if n == 0 {
+        return out;
+    }
+
+    // Some optimum only uses each row's `n` cheapest columns (any other column could be
+    // swapped for one of those left free), so solve over their union.
+    let mut keep = BTreeSet::new();
+    for &i in &rows {
+        let mut allowed: Vec<(f64, usize)> = costs[i]
+            .iter()
+            .enumerate()
+            .filter_map(|(j, c)| c.map(|c| (c, j)))
+            .collect();
+        allowed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        keep.extend(allowed.into_iter().take(n).map(|(_, j)| j));
+    }
+    let keep: Vec<usize> = keep.into_iter().collect();
+    let reduced: Vec<Vec<Option<f64>>> = rows
+        .iter()
+        .map(|&i| keep.iter().map(|&j| costs[i][j]).collect())
+        .collect();
+
+    for (&i, pick) in rows.iter().zip(solve(&reduced)) {
+        out[i] = pick.map(|j| keep[j]);
+    }
+    out
+}
+
+/// Hungarian method over the full matrix.
+///
+/// Forbidden cells are skipped rather than priced, so the potentials stay on the scale
+/// of the real costs (small tie-breaks survive). Rows that run out of columns land in
+/// fallback columns priced above anything a real pick could save elsewhere.
+fn solve(costs: &[Vec<Option<f64>>]) -> Vec<Option<usize>> {
+    let n = costs.len();
+    let real = costs.iter().map(Vec::len).max().unwrap_or(0);
+    let (lo, hi) = costs
+        .iter()
+        .flatten()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &c| {
+            (lo.min(c), hi.max(c))
+        });
+    let fallback = hi + (hi - lo) * n as f64 + 1.0;
+    let cols = real + n;
+    let cell = |i: usize, j: usize| -> Option<f64> {
+        if j < real {
+            costs[i].get(j).copied().flatten()
+        } else {
+            Some(fallback)
+        }
+    };
+
+    // 1-based potentials / matching, column 0 is the virtual start.
+    let mut u = vec![0.0f64; n + 1];
+    let mut v = vec![0.0f64; cols + 1];
+    let mut row_of = vec![0usize; cols + 1];
+    let mut way = vec![0usize; cols + 1];
+
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut j0 = 0usize;
+        let mut minv = vec![f64::INFINITY; cols + 1];
+        let mut used = vec![false; cols + 1];
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0usize;
+            for j in 1..=cols {
+                if used[j] {
+                    continue;
+                }
+                if let Some(c) = cell(i0 - 1, j - 1) {
+                    let cur = c - u[i0] - v[j];
+                    if cur < minv[j] {
+                        minv[j] = cur;
+                        way[j] = j0;
+                    }
+                }
+                if minv[j] < delta {
+                    delta = minv[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=cols {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path.
+        loop {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut out = vec![None; n];
+    for (j, &i) in row_of.iter().enumerate().skip(1).take(real) {
+        if i > 0 {
+            out[i - 1] = Some(j - 1);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Best (assigned rows, total cost) by trying every assignment.
+    fn brute_force(costs: &[Vec<Option<f64>>]) -> (usize, f64) {
+        fn go(costs: &[Vec<Option<f64>>], row: usize, used: &mut Vec<bool>) -> (usize, f64) {
+            let Some(cells) = costs.get(row) else {
+                return (0, 0.0);
+            };
+            let mut best = go(costs, row + 1, used);
+            for (j, cell) in cells.iter().enumerate() {
+                let Some(c) = *cell else { continue };
+                if used[j] {
+                    continue;
+                }
+                used[j] = true;
+                let (n, total) = go(costs, row + 1, used);
+                used[j] = false;
+                if n + 1 > best.0 || (n + 1 == best.0 && total + c < best.1) {
+                    best = (n + 1, total + c);
+                }
+            }
+            best
+        }
+        let cols = costs.iter().map(Vec::len).max().unwrap_or(0);
+        go(costs, 0, &mut vec![false; cols])
+    }
+
+    /// (assigned rows, total cost) of `picks`, checking they're allowed and distinct.
+    fn score(costs: &[Vec<Option<f64>>], picks: &[Option<usize>]) -> (usize, f64) {
+        assert_eq!(picks.len(), costs.len());
+        let mut seen = BTreeSet::new();
+        let mut total = 0.0;
+        for (row, pick) in costs.iter().zip(picks) {
+            let Some(j) = *pick else { continue };
+            assert!(seen.insert(j), "column {j} picked twice");
+            total += row[j].expect("forbidden cell picked");
+        }
+        (seen.len(), total)
+    }
+
+    /// Small random matrices from a fixed LCG: about a third of the cells forbidden.
+    fn matrices() -> Vec<Vec<Vec<Option<f64>>>> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move |m: u64| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) % m
+        };
+        (0..300)
+            .map(|_| {
+                let rows = 1 + next(5) as usize;
+                let cols = 1 + next(5) as usize;
+                (0..rows)
+                    .map(|_| {
+                        (0..cols)
+                            .map(|_| (next(3) != 0).then(|| next(10) as f64))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for costs in matrices() {
+            let (n, total) = score(&costs, &min_cost_assignment(&costs));
+            let (best_n, best_total) = brute_force(&costs);
+            assert_eq!(n, best_n, "{costs:?}");
+            assert!((total - best_total).abs() < 1e-9, "{costs:?}");
+        }
+    }
+
+    #[test]
+    fn never_picks_forbidden_cells() {
+        let costs = vec![
+            vec![None, Some(5.0), None],
+            vec![None, None, None],
+            vec![Some(1.0), None, None],
+        ];
+        assert_eq!(min_cost_assignment(&costs), [Some(1), None, Some(0)]);
+    }
+
+    #[test]
+    fn more_rows_than_columns() {
+        let costs = vec![
+            vec![Some(3.0), Some(1.0)],
+            vec![Some(1.0), Some(3.0)],
+            vec![Some(0.0), Some(0.0)],
+        ];
+        let picks = min_cost_assignment(&costs);
+        assert_eq!(score(&costs, &picks), (2, 1.0));
+        assert_eq!(picks.iter().filter(|p| p.is_none()).count(), 1);
+    }
+
+    #[test]
+    fn ties_go_to_earlier_rows_and_columns() {
+        let costs = vec![vec![Some(1.0); 3]; 3];
+        assert_eq!(min_cost_assignment(&costs), [Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn small_tie_breaks_survive_forbidden_cells() {
+        // Column order nudges like the generator's `TIE_BREAK`; row 1 loses column 0
+        let e = 1e-6;
+        let nudged = vec![
+            None,
+            Some(2.0 + e),
+            Some(2.0 + 2.0 * e),
+            Some(2.0 + 3.0 * e),
+        ];
+        let costs = vec![
+            vec![Some(1.0), None, None, None],
+            vec![Some(1.0), None, None, None],
+            nudged.clone(),
+            nudged,
+        ];
+        assert_eq!(
+            min_cost_assignment(&costs),
+            [Some(0), None, Some(1), Some(2)]
+        );
+    }
+}
@@ -4,6 +4,8 @@
 //! - This module re-exports `Key` and `MouseButton` so downstreams can depend
 //!   only on `streamdeck_sc_core` instead of `streamdeck-lib` directly.
 //! - Parsing accepts a liberal set of aliases and device prefixes to match SC XML.
+//! - Gamepad/joystick tokens overlap with keyboard ones ("x" is a key, a gamepad button
+//!   and a joystick axis), so they are only recognized with a `DeviceKind` context.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -13,6 +15,7 @@ use std::str::FromStr;
 
 pub use streamdeck_lib::input::{Key, MouseButton};
 
+use crate::bindings::activation_mode::ActivationArena;
 use crate::bindings::constants::CANDIDATE_MODIFIERS;
 
 /// Origin of a bind.
@@ -25,6 +28,112 @@ pub enum BindOrigin {
     Generated,
 }
 
+/// Where a bind the game uses for an action comes from (see `ActionBinding::device_binds`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum BindSource {
+    /// Game default, not overridden for this device.
+    Default,
+    /// The user's own rebind.
+    Custom,
+    /// Produced by `BindGenerator`.
+    Generated,
+}
+
+impl BindSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BindSource::Default => "default",
+            BindSource::Custom => "custom",
+            BindSource::Generated => "generated",
+        }
+    }
+}
+
+impl fmt::Display for BindSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Input device family a bind belongs to (matches the SC XML attribute/tag names).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeviceKind {
+    Keyboard,
+    Mouse,
+    Gamepad,
+    Joystick,
+}
+
+impl DeviceKind {
+    pub const ALL: [DeviceKind; 4] = [
+        DeviceKind::Keyboard,
+        DeviceKind::Mouse,
+        DeviceKind::Gamepad,
+        DeviceKind::Joystick,
+    ];
+
+    /// XML attribute / tag name ("keyboard", "mouse", "gamepad", "joystick").
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceKind::Keyboard => "keyboard",
+            DeviceKind::Mouse => "mouse",
+            DeviceKind::Gamepad => "gamepad",
+            DeviceKind::Joystick => "joystick",
+        }
+    }
+
+    /// Rebind input prefix without the instance ("kb", "mo", "gp", "js").
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            DeviceKind::Keyboard => "kb",
+            DeviceKind::Mouse => "mo",
+            DeviceKind::Gamepad => "gp",
+            DeviceKind::Joystick => "js",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        DeviceKind::ALL.into_iter().find(|d| d.name() == s)
+    }
+
+    pub fn from_prefix(s: &str) -> Option<Self> {
+        DeviceKind::ALL.into_iter().find(|d| d.prefix() == s)
+    }
+}
+
+impl fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Concrete device a bind was read from, e.g. "js2_" → `Joystick` instance 2.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct DeviceRef {
+    pub kind: DeviceKind,
+    /// SC instance number (1-based, as in "kb1_" / "js2_").
+    pub instance: u8,
+}
+
+impl DeviceRef {
+    #[inline]
+    pub fn new(kind: DeviceKind, instance: u8) -> Self {
+        Self { kind, instance }
+    }
+
+    /// Rebind input prefix including the instance, e.g. "js2_".
+    pub fn prefix(&self) -> String {
+        format!("{}{}_", self.kind.prefix(), self.instance)
+    }
+}
+
+impl fmt::Display for DeviceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.kind.prefix(), self.instance)
+    }
+}
+
 /// Main part of a bind (the "key" side).
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BindMain {
@@ -36,6 +145,18 @@ pub enum BindMain {
     MouseAxis(String),
     /// e.g. "hmd_pitch"
     HMD(String),
+    /// e.g. "a", "shoulderl", "dpad_up", "thumbl"
+    GamepadButton(String),
+    /// e.g. "triggerl_btn", "triggerr"
+    GamepadTrigger(String),
+    /// Stick axis or stick direction, e.g. "thumblx", "thumbr_up"
+    GamepadAxis(String),
+    /// e.g. 5 for "button5"
+    JoystickButton(u16),
+    /// Hat number + direction, e.g. (1, "up") for "hat1_up"
+    JoystickHat(u8, String),
+    /// e.g. "x", "rotz", "slider1"
+    JoystickAxis(String),
     Unsupported,
 }
 
@@ -48,22 +169,43 @@ impl fmt::Display for BindMain {
             BindMain::MouseWheelDown => write!(f, "mwheel_down"),
             BindMain::MouseAxis(s) => write!(f, "maxis({s})"),
             BindMain::HMD(s) => write!(f, "hmd({s})"),
+            BindMain::GamepadButton(s) | BindMain::GamepadTrigger(s) | BindMain::GamepadAxis(s) => {
+                write!(f, "gp({s})")
+            }
+            BindMain::JoystickButton(n) => write!(f, "js(button{n})"),
+            BindMain::JoystickHat(n, dir) => write!(f, "js(hat{n}_{dir})"),
+            BindMain::JoystickAxis(s) => write!(f, "js({s})"),
             BindMain::Unsupported => write!(f, "<unsupported>"),
         }
     }
 }
 
 impl BindMain {
+    /// True if this input can't be simulated from a Stream Deck (wheel, axes, HMD,
+    /// gamepad and joystick inputs).
     #[inline]
     pub fn is_unsupported(&self) -> bool {
-        matches!(
-            self,
-            BindMain::Unsupported
-                | BindMain::MouseWheelUp
-                | BindMain::MouseWheelDown
-                | BindMain::MouseAxis(_)
-                | BindMain::HMD(_)
-        )
+        !matches!(self, BindMain::Key(_) | BindMain::Mouse(_))
+    }
+
+    /// Device this input is written under when it has no device context of its own.
+    /// Wheel and mouse axes belong to the mouse; HMD stays on the keyboard side (best
+    /// available bucket, as in `bind_to_input_with_prefix`).
+    #[inline]
+    pub fn device_kind(&self) -> DeviceKind {
+        match self {
+            BindMain::Mouse(_)
+            | BindMain::MouseWheelUp
+            | BindMain::MouseWheelDown
+            | BindMain::MouseAxis(_) => DeviceKind::Mouse,
+            BindMain::GamepadButton(_) | BindMain::GamepadTrigger(_) | BindMain::GamepadAxis(_) => {
+                DeviceKind::Gamepad
+            }
+            BindMain::JoystickButton(_) | BindMain::JoystickHat(..) | BindMain::JoystickAxis(_) => {
+                DeviceKind::Joystick
+            }
+            _ => DeviceKind::Keyboard,
+        }
     }
 }
 
@@ -72,15 +214,27 @@ impl BindMain {
 pub struct Bind {
     pub main: Option<BindMain>,
     pub modifiers: HashSet<Key>,
+    /// Gamepad/joystick modifiers in input order, e.g. `shoulderl` in "shoulderl+y".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub device_modifiers: Vec<BindMain>,
 
     /// Index into ActivationArena (external to this module).
     pub activation_mode_idx: Option<usize>,
 
+    /// Device instance from an explicit prefix ("kb2_", "js1_"). `None` for unprefixed
+    /// defaults and generated binds, which use the emitter's device list instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<DeviceRef>,
+
     /// True if explicitly unbound (no main key + no modifiers).
     pub is_unbound: bool,
 
     #[serde(default)]
     pub origin: BindOrigin,
+
+    /// Unrecognized `<rebind>` attributes from the custom profile, re-emitted as-is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_attrs: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone)]
@@ -108,8 +262,10 @@ impl std::error::Error for BindParseError {}
 
 impl PartialEq for Bind {
     fn eq(&self, other: &Self) -> bool {
-        // Intentionally ignore activation_mode_idx and is_unbound for equality
-        self.main == other.main && self.modifiers == other.modifiers
+        // Intentionally ignore activation_mode_idx, device, extra_attrs and is_unbound for equality
+        self.main == other.main
+            && self.modifiers == other.modifiers
+            && self.device_modifiers == other.device_modifiers
     }
 }
 impl Eq for Bind {}
@@ -125,6 +281,7 @@ impl Hash for Bind {
         for s in mods_as_strings {
             s.hash(state);
         }
+        self.device_modifiers.hash(state);
     }
 }
 
@@ -133,6 +290,7 @@ impl fmt::Display for Bind {
         // Deterministic ordering of modifiers for display
         let mut mods: Vec<String> = self.modifiers.iter().map(|k| k.to_string()).collect();
         mods.sort_unstable();
+        mods.extend(self.device_modifiers.iter().map(|m| m.to_string()));
 
         let mods_joined = mods.join("+");
         let main = self
@@ -140,15 +298,29 @@ impl fmt::Display for Bind {
             .as_ref()
             .map_or("<none>".to_string(), |k| k.to_string());
 
+        // `pad` so column widths in reports apply
         if mods_joined.is_empty() {
-            write!(f, "{main}")
+            f.pad(&main)
         } else {
-            write!(f, "{mods_joined}+{main}")
+            f.pad(&format!("{mods_joined}+{main}"))
         }
     }
 }
 
 impl Bind {
+    /// Equality that knows about activation modes: the same input, and the two modes
+    /// (arena indices, the bind's own or its action's) would fire on the same gesture.
+    /// `f` as tap and `f` as delayed_press don't collide; `PartialEq` says they're equal.
+    pub fn collides_with(
+        &self,
+        mode: Option<usize>,
+        other: &Bind,
+        other_mode: Option<usize>,
+        modes: &ActivationArena,
+    ) -> bool {
+        self == other && !modes.can_share(mode, other_mode)
+    }
+
     #[inline]
     pub fn is_executable(&self) -> bool {
         !self.is_unbound && self.main.is_some() && !self.main.as_ref().unwrap().is_unsupported()
@@ -164,9 +336,12 @@ impl Bind {
         Bind {
             main: mainkey,
             modifiers,
+            device_modifiers: Vec::new(),
             activation_mode_idx,
+            device: None,
             is_unbound,
             origin: BindOrigin::User,
+            extra_attrs: Vec::new(),
         }
     }
 
@@ -179,40 +354,75 @@ impl Bind {
         Bind {
             main: Some(mainkey),
             modifiers,
+            device_modifiers: Vec::new(),
             activation_mode_idx: press_mode,
+            device: None,
             is_unbound: false,
             origin: BindOrigin::Generated,
+            extra_attrs: Vec::new(),
         }
     }
 
     /// Parse a bind from a string like:
-    ///   "lctrl+f", "LShift+A", "np_1", "kb1_lctrl+f", "" (empty → explicit unbind)
+    ///   "lctrl+f", "LShift+A", "np_1", "kb1_lctrl+f", "js1_button3", "" (empty → explicit unbind)
     ///
+    /// Without a device prefix the input is read as keyboard/mouse.
     /// `activation_mode_idx` is stored as-is (index into ActivationArena).
     pub fn from_string(
         input: &str,
         activation_mode_idx: Option<usize>,
     ) -> Result<Self, BindParseError> {
-        // Empty → explicit unbound
-        if input.trim().is_empty() {
+        Self::from_device_string(input, DeviceKind::Keyboard, activation_mode_idx)
+    }
+
+    /// Parse a bind in the context of `device` (e.g. the value of `gamepad="..."`).
+    /// A device prefix in `input` ("gp1_", "js2_", ...) wins over `device`, and its
+    /// instance is kept in `Bind::device`.
+    pub fn from_device_string(
+        input: &str,
+        device: DeviceKind,
+        activation_mode_idx: Option<usize>,
+    ) -> Result<Self, BindParseError> {
+        // Strip known device prefixes (keep token semantics like "np_1")
+        let (prefixed, instance, parts) = strip_device_prefix(input.trim());
+        let device_ref = prefixed.zip(instance).map(|(k, i)| DeviceRef::new(k, i));
+
+        // Empty (or a bare prefix like "kb1_ ") → explicit unbound
+        if parts.trim().is_empty() {
             return Ok(Bind {
                 main: None,
                 modifiers: HashSet::new(),
+                device_modifiers: Vec::new(),
                 activation_mode_idx,
+                device: device_ref,
                 is_unbound: true,
                 origin: BindOrigin::User,
+                extra_attrs: Vec::new(),
             });
         }
 
-        // Strip known device prefixes (keep token semantics like "np_1")
-        let parts = strip_device_prefix(input);
-
         let segments: Vec<&str> = parts
             .split('+')
             .map(str::trim)
             .filter(|s| !s.is_empty())
             .collect();
 
+        let mut bind = match prefixed.unwrap_or(device) {
+            DeviceKind::Keyboard | DeviceKind::Mouse => {
+                Self::from_kbm_segments(input, segments, activation_mode_idx)
+            }
+            kind => Self::from_controller_segments(kind, segments, activation_mode_idx),
+        }?;
+        bind.device = device_ref;
+        Ok(bind)
+    }
+
+    /// Keyboard/mouse tokens: keyboard modifiers + exactly one main input.
+    fn from_kbm_segments(
+        input: &str,
+        segments: Vec<&str>,
+        activation_mode_idx: Option<usize>,
+    ) -> Result<Self, BindParseError> {
         let mut modifiers = HashSet::new();
         let mut main_keys: Vec<BindMain> = Vec::new();
 
@@ -272,9 +482,12 @@ impl Bind {
                 Ok(Bind {
                     main: Some(BindMain::Key(mainkey)),
                     modifiers: HashSet::new(),
+                    device_modifiers: Vec::new(),
                     activation_mode_idx,
+                    device: None,
                     is_unbound: false,
                     origin: BindOrigin::User,
+                    extra_attrs: Vec::new(),
                 })
             }
             1 => {
@@ -282,9 +495,12 @@ impl Bind {
                 Ok(Bind {
                     main: Some(mainkey),
                     modifiers,
+                    device_modifiers: Vec::new(),
                     activation_mode_idx,
+                    device: None,
                     is_unbound: false,
                     origin: BindOrigin::User,
+                    extra_attrs: Vec::new(),
                 })
             }
             _ => Err(BindParseError::TooManyMainKeys {
@@ -293,6 +509,49 @@ impl Bind {
             }),
         }
     }
+
+    /// Gamepad/joystick tokens: the last input is the main one, earlier inputs are
+    /// device modifiers ("shoulderl+y"). Keyboard modifiers are still accepted.
+    fn from_controller_segments(
+        device: DeviceKind,
+        segments: Vec<&str>,
+        activation_mode_idx: Option<usize>,
+    ) -> Result<Self, BindParseError> {
+        let mut modifiers = HashSet::new();
+        let mut inputs: Vec<BindMain> = Vec::new();
+
+        for seg in segments {
+            let s = seg.to_ascii_lowercase();
+
+            let parsed = match device {
+                DeviceKind::Gamepad => gamepad_alias(&s),
+                _ => joystick_alias(&s),
+            };
+            if let Some(main) = parsed {
+                inputs.push(main);
+                continue;
+            }
+
+            match Key::parse(&s) {
+                Some(key) if CANDIDATE_MODIFIERS.contains(&key) => {
+                    modifiers.insert(key);
+                }
+                _ => return Err(BindParseError::NoInput),
+            }
+        }
+
+        let main = inputs.pop().ok_or(BindParseError::NoInput)?;
+        Ok(Bind {
+            main: Some(main),
+            modifiers,
+            device_modifiers: inputs,
+            activation_mode_idx,
+            device: None,
+            is_unbound: false,
+            origin: BindOrigin::User,
+            extra_attrs: Vec::new(),
+        })
+    }
 }
 
 impl FromStr for Bind {
@@ -306,18 +565,60 @@ impl FromStr for Bind {
 
 // ───────────────────────────── helpers ─────────────────────────────
 
-/// Only strip prefixes we actually expect from SC XML like "kb1_", "mo1_", "gp1_".
-fn strip_device_prefix(s: &str) -> &str {
-    // NOTE: SC device instance prefixes vary; keep this liberal but finite.
-    const PREFIXES: &[&str] = &[
-        "kb1_", "kb2_", "kb_", "mo1_", "mo2_", "mo_", "gp1_", "gp2_", "gp_", "js1_", "js2_", "js_",
-    ];
-    for p in PREFIXES {
-        if let Some(end) = s.strip_prefix(p) {
-            return end;
+/// Split a device prefix like "kb1_", "js2_" or "gp_" off `s`.
+/// Returns the device it names and its instance (if numbered), or `None` (and `s` unchanged)
+/// if there is no prefix.
+pub(crate) fn strip_device_prefix(s: &str) -> (Option<DeviceKind>, Option<u8>, &str) {
+    let Some(kind) = s.get(..2).and_then(DeviceKind::from_prefix) else {
+        return (None, None, s);
+    };
+    let rest = &s[2..];
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    match rest[digits..].strip_prefix('_') {
+        Some(end) => (Some(kind), rest[..digits].parse::<u8>().ok(), end),
+        None => (None, None, s),
+    }
+}
+
+/// Gamepad tokens as used by SC ("a", "shoulderl", "dpad_up", "triggerl_btn", "thumblx", ...).
+fn gamepad_alias(s: &str) -> Option<BindMain> {
+    if s.starts_with("trigger") {
+        return Some(BindMain::GamepadTrigger(s.into()));
+    }
+    if let Some(rest) = s.strip_prefix("thumb") {
+        return match rest {
+            "l" | "r" => Some(BindMain::GamepadButton(s.into())),
+            _ if rest.len() > 1 && (rest.starts_with('l') || rest.starts_with('r')) => {
+                Some(BindMain::GamepadAxis(s.into()))
+            }
+            _ => None,
+        };
+    }
+    match s {
+        "a" | "b" | "x" | "y" | "start" | "back" | "shoulderl" | "shoulderr" => {
+            Some(BindMain::GamepadButton(s.into()))
         }
+        _ if s.starts_with("dpad_") => Some(BindMain::GamepadButton(s.into())),
+        _ => None,
+    }
+}
+
+/// Joystick tokens as used by SC ("button5", "hat1_up", "x", "rotz", "slider1", ...).
+fn joystick_alias(s: &str) -> Option<BindMain> {
+    if let Some(n) = s.strip_prefix("button").and_then(|n| n.parse::<u16>().ok()) {
+        return Some(BindMain::JoystickButton(n));
+    }
+    if let Some((n, dir)) = s.strip_prefix("hat").and_then(|r| r.split_once('_')) {
+        return n
+            .parse::<u8>()
+            .ok()
+            .map(|n| BindMain::JoystickHat(n, dir.into()));
+    }
+    match s {
+        "x" | "y" | "z" | "rotx" | "roty" | "rotz" => Some(BindMain::JoystickAxis(s.into())),
+        _ if s.starts_with("slider") => Some(BindMain::JoystickAxis(s.into())),
+        _ => None,
     }
-    s
 }
 
 fn mouse_alias(seg: &str) -> Option<MouseButton> {
@@ -0,0 +1,194 @@
+//! Ergonomic cost of a generated keyboard bind (lower is better).
+//!
+//! Positions are on a US ANSI board in key widths (`x` to the right, `y` down from the
+//! function row). The left hand is assumed to rest on WASD, the right hand on the mouse.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::bindings::{
+    bind::{BindMain, Key},
+    bind_tokens::KeyTokenExt,
+};
+
+/// Reach assumed for keys without a known position.
+const UNKNOWN_REACH: f32 = 12.0;
+
+/// Weights of the cost terms; all default to a sensible mix, set one to 0 to ignore it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CostWeights {
+    /// Per key width of distance between the main key and the nearest of W/A/S/D.
+    pub reach: f32,
+    /// Per modifier held.
+    pub modifier: f32,
+    /// Extra per right-hand modifier (the right hand has to leave the mouse).
+    pub right_modifier: f32,
+    /// Per key width of distance between the main key and the centre of the keys already
+    /// bound in the same action map (keeps related actions together).
+    pub proximity: f32,
+    /// Flat cost of a mouse button or the wheel as main input (instead of reach/proximity).
+    pub mouse: f32,
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        Self {
+            reach: 1.0,
+            modifier: 2.0,
+            right_modifier: 1.5,
+            proximity: 0.5,
+            mouse: 4.0,
+        }
+    }
+}
+
+impl CostWeights {
+    /// Cost of `main` held with `modifiers`; `anchor` is the centre of the related keys
+    /// (see `centroid`), if there are any.
+    pub fn cost(
+        &self,
+        main: &BindMain,
+        modifiers: &HashSet<Key>,
+        anchor: Option<(f32, f32)>,
+    ) -> f32 {
+        let right = modifiers.iter().filter(|m| is_right_modifier(**m)).count();
+        let modifier_cost =
+            self.modifier * modifiers.len() as f32 + self.right_modifier * right as f32;
+
+        let BindMain::Key(key) = main else {
+            return self.mouse + modifier_cost;
+        };
+        let pos = key_position(*key);
+        let reach = pos.map_or(UNKNOWN_REACH, |p| {
+            WASD.iter()
+                .map(|&k| distance(p, key_position(k).unwrap_or_default()))
+                .fold(f32::MAX, f32::min)
+        });
+        let proximity = match (pos, anchor) {
+            (Some(p), Some(a)) => distance(p, a),
+            _ => 0.0,
+        };
+
+        self.reach * reach + modifier_cost + self.proximity * proximity
+    }
+}
+
+const WASD: [Key; 4] = [Key::W, Key::A, Key::S, Key::D];
+
+/// Centre of the positioned keys in `keys` (`None` if there are none).
+pub fn centroid(keys: impl IntoIterator<Item = Key>) -> Option<(f32, f32)> {
+    let (mut sx, mut sy, mut n) = (0.0, 0.0, 0usize);
+    for (x, y) in keys.into_iter().filter_map(key_position) {
+        sx += x;
+        sy += y;
+        n += 1;
+    }
+    (n > 0).then(|| (sx / n as f32, sy / n as f32))
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+fn is_right_modifier(key: Key) -> bool {
+    matches!(key, Key::RShift | Key::RCtrl | Key::RAlt | Key::RWin)
+}
+
+/// Physical position of a key (centre, in key widths).
+pub fn key_position(key: Key) -> Option<(f32, f32)> {
+    const ROWS: &[(f32, f32, &[&str])] = &[
+        // (y, x of first key, keys one width apart)
+        (
+            1.5,
+            1.0,
+            &[
+                "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "minus", "equals",
+            ],
+        ),
+        (
+            2.5,
+            1.5,
+            &[
+                "q", "w", "e", "r", "t", "y", "u", "i", "o", "p", "lbracket", "rbracket",
+            ],
+        ),
+        (
+            3.5,
+            1.75,
+            &[
+                "a",
+                "s",
+                "d",
+                "f",
+                "g",
+                "h",
+                "j",
+                "k",
+                "l",
+                "semicolon",
+                "apostrophe",
+            ],
+        ),
+        (
+            4.5,
+            2.25,
+            &[
+                "z", "x", "c", "v", "b", "n", "m", "comma", "period", "slash",
+            ],
+        ),
+        (0.0, 2.0, &["f1", "f2", "f3", "f4"]),
+        (0.0, 6.5, &["f5", "f6", "f7", "f8"]),
+        (0.0, 11.0, &["f9", "f10", "f11", "f12"]),
+        (1.5, 15.25, &["insert", "home", "pgup"]),
+        (2.5, 15.25, &["delete", "end", "pgdn"]),
+        (5.5, 15.25, &["left", "down", "right"]),
+        (
+            1.5,
+            18.5,
+            &["np_lock", "np_divide", "np_multiply", "np_subtract"],
+        ),
+        (2.5, 18.5, &["np_7", "np_8", "np_9"]),
+        (3.5, 18.5, &["np_4", "np_5", "np_6"]),
+        (4.5, 18.5, &["np_1", "np_2", "np_3"]),
+    ];
+    const SINGLES: &[(&str, f32, f32)] = &[
+        ("escape", 0.0, 0.0),
+        ("grave", 0.0, 1.5),
+        ("backspace", 14.0, 1.5),
+        ("tab", 0.25, 2.5),
+        ("backslash", 13.75, 2.5),
+        ("capslock", 0.4, 3.5),
+        ("enter", 13.4, 3.5),
+        ("lshift", 0.6, 4.5),
+        ("rshift", 12.9, 4.5),
+        ("lctrl", 0.6, 5.5),
+        ("lwin", 1.9, 5.5),
+        ("lalt", 3.1, 5.5),
+        ("space", 6.9, 5.5),
+        ("ralt", 10.6, 5.5),
+        ("rwin", 11.9, 5.5),
+        ("menu", 13.1, 5.5),
+        ("rctrl", 14.4, 5.5),
+        ("print", 15.25, 0.0),
+        ("pause", 17.25, 0.0),
+        ("up", 16.25, 4.5),
+        ("np_add", 21.5, 3.0),
+        ("np_enter", 21.5, 5.0),
+        ("np_0", 19.0, 5.5),
+        ("np_period", 20.5, 5.5),
+    ];
+
+    let tok = key.to_token();
+    ROWS.iter()
+        .find_map(|(y, x0, keys)| {
+            let i = keys.iter().position(|k| *k == tok)?;
+            Some((x0 + i as f32, *y))
+        })
+        .or_else(|| {
+            SINGLES
+                .iter()
+                .find(|(k, _, _)| *k == tok)
+                .map(|(_, x, y)| (*x, *y))
+        })
+}
@@ -3,7 +3,7 @@
 
 use std::collections::HashSet;
 
-use crate::bindings::bind::{BindMain, Key, MouseButton};
+use crate::bindings::bind::{Bind, BindMain, DeviceKind, Key, MouseButton};
 
 /// Local trait so we can render external `Key` into the XML token vocabulary.
 pub trait KeyTokenExt {
@@ -161,57 +161,105 @@ fn mod_bucket(tok: &str) -> u8 {
     }
 }
 
+/// Modifier tokens in game order (ctrl, alt, shift, then alpha).
+pub(crate) fn modifier_tokens(mods: &HashSet<Key>) -> Vec<&'static str> {
+    let mut kb: Vec<&'static str> = mods.iter().map(|k| k.to_token()).collect();
+    kb.sort_by(|a, b| mod_bucket(a).cmp(&mod_bucket(b)).then(a.cmp(b)));
+    kb
+}
+
 /// Build the `<rebind input="...">` token without the device prefix.
-pub fn bind_to_token_no_prefix(main: &Option<BindMain>, mods: &HashSet<Key>) -> Option<String> {
+/// Gamepad/joystick modifiers follow the keyboard modifiers in input order.
+pub fn bind_to_token_no_prefix(
+    main: &Option<BindMain>,
+    mods: &HashSet<Key>,
+    device_mods: &[BindMain],
+) -> Option<String> {
     let main = main.as_ref()?;
 
     // mods → tokens, ordered
-    let mut m: Vec<&'static str> = mods.iter().map(|k| k.to_token()).collect();
-    m.sort_by(|a, b| mod_bucket(a).cmp(&mod_bucket(b)).then(a.cmp(b)));
+    let mut m: Vec<String> = modifier_tokens(mods)
+        .into_iter()
+        .map(String::from)
+        .collect();
+    for dm in device_mods {
+        m.push(main_to_token(dm)?);
+    }
+
+    let main_tok = main_to_token(main)?;
 
-    // main → token
-    if main.is_unsupported() {
-        return None;
+    if m.is_empty() {
+        Some(main_tok)
+    } else {
+        let mut s = m.join("+");
+        s.push('+');
+        s.push_str(&main_tok);
+        Some(s)
     }
+}
 
-    let main_tok = match main {
+/// Token for a single main input (no modifiers, no device prefix).
+fn main_to_token(main: &BindMain) -> Option<String> {
+    Some(match main {
         BindMain::Key(k) => k.to_token().into(),
         BindMain::Mouse(btn) => mouse_to_token(*btn).into(),
         BindMain::MouseWheelUp => "mwheel_up".into(),
         BindMain::MouseWheelDown => "mwheel_down".into(),
         BindMain::MouseAxis(axis) => format!("maxis_{axis}"),
         BindMain::HMD(axis) => format!("hmd_{axis}"),
+        BindMain::GamepadButton(s) | BindMain::GamepadTrigger(s) | BindMain::GamepadAxis(s) => {
+            s.clone()
+        }
+        BindMain::JoystickButton(n) => format!("button{n}"),
+        BindMain::JoystickHat(n, dir) => format!("hat{n}_{dir}"),
+        BindMain::JoystickAxis(axis) => axis.clone(),
         BindMain::Unsupported => return None,
-    };
+    })
+}
 
-    if m.is_empty() {
-        Some(main_tok)
-    } else {
-        let mut s = m.join("+");
-        s.push('+');
-        s.push_str(&main_tok);
-        Some(s)
+/// Fallback device instance ids for binds without an explicit `Bind::device`
+/// ("kb{inst}_", "mo{inst}_", "gp{inst}_", "js{inst}_").
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceInstances<'a> {
+    pub keyboard: &'a str,
+    pub mouse: &'a str,
+    pub gamepad: &'a str,
+    pub joystick: &'a str,
+}
+
+impl DeviceInstances<'_> {
+    fn get(&self, kind: DeviceKind) -> &str {
+        match kind {
+            DeviceKind::Keyboard => self.keyboard,
+            DeviceKind::Mouse => self.mouse,
+            DeviceKind::Gamepad => self.gamepad,
+            DeviceKind::Joystick => self.joystick,
+        }
     }
 }
 
-/// Full token with device prefix ("kb{inst}_" or "mo{inst}_").
+/// Full token with device prefix ("kb{inst}_", "mo{inst}_", "gp{inst}_" or "js{inst}_").
 /// Wheel and mouse axes are emitted under the **mouse** device.
 /// HMD axes are emitted under the **keyboard** device (best available bucket).
-pub fn bind_to_input_with_prefix(
-    main: &Option<BindMain>,
-    mods: &HashSet<Key>,
-    kb_inst: &str,
-    mo_inst: &str,
-) -> Option<String> {
-    let no_prefix = bind_to_token_no_prefix(main, mods)?;
-
-    match main.as_ref()? {
-        BindMain::Key(_) => Some(format!("kb{kb_inst}_{no_prefix}")),
-        BindMain::Mouse(_) => Some(format!("mo{mo_inst}_{no_prefix}")),
-        BindMain::MouseWheelUp => Some(format!("mo{mo_inst}_mwheel_up")),
-        BindMain::MouseWheelDown => Some(format!("mo{mo_inst}_mwheel_down")),
-        BindMain::MouseAxis(axis) => Some(format!("mo{mo_inst}_maxis_{axis}")),
-        BindMain::HMD(axis) => Some(format!("kb{kb_inst}_hmd_{axis}")),
-        BindMain::Unsupported => None,
+/// The bind's own instance wins when it was read from the same device kind.
+pub fn bind_to_input_with_prefix(bind: &Bind, inst: &DeviceInstances) -> Option<String> {
+    let main = bind.main.as_ref()?;
+    let no_prefix = bind_to_token_no_prefix(&bind.main, &bind.modifiers, &bind.device_modifiers)?;
+
+    let kind = match main {
+        BindMain::Unsupported => return None,
+        other => other.device_kind(),
+    };
+    let instance = match bind.device {
+        Some(d) if d.kind == kind => d.instance.to_string(),
+        _ => inst.get(kind).to_string(),
+    };
+
+    match main {
+        // Wheel ignores modifiers (historical choice)
+        BindMain::MouseWheelUp => Some(format!("mo{instance}_mwheel_up")),
+        BindMain::MouseWheelDown => Some(format!("mo{instance}_mwheel_down")),
+        BindMain::MouseAxis(axis) => Some(format!("mo{instance}_maxis_{axis}")),
+        _ => Some(format!("{}{instance}_{no_prefix}", kind.prefix())),
     }
 }
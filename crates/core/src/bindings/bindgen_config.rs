@@ -0,0 +1,270 @@
+//! Versioned bind-generation config (JSON), shared by the CLI and the plugin.
+//!
+//! Every field is optional; anything left out keeps the built-in default from
+//! `constants`, so an empty object (`{}`) builds the same generator as
+//! `BindGenerator::with_logger`. `ActionBindings::generate_missing_binds_with` then
+//! adapts it to the profile: its declared `<modifiers>` replace the modifier pool
+//! (unless `candidate_modifiers` is set) and are taken out of the key pool.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    CoreLog,
+    bindings::{
+        activation_mode::ActivationArena,
+        bind::{Bind, BindMain, Key, MouseButton},
+        bind_cost::CostWeights,
+        binds_generator::BindGenerator,
+        constants::CATEGORY_GROUPS,
+        str_intern::intern,
+    },
+};
+
+/// Newest config format this build understands.
+pub const BINDGEN_CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BindGenConfig {
+    /// Format version (files without one are read as version 1).
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// e.g. ["f1","f2","np_1","u","i","up", ...] (listed order is the default priority)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_keys: Option<Vec<String>>,
+    /// e.g. ["lshift","rshift","lctrl","lalt"] (listed order is the default priority).
+    /// Left out, the profile's declared `<modifiers>` are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_modifiers: Option<Vec<String>>,
+    /// Keys tried first, in this order, e.g. ["f1","f2","np_1"]; the rest follow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_priority: Option<Vec<String>>,
+    /// Modifiers tried first, in this order, e.g. ["lctrl","lalt"]; the rest follow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier_priority: Option<Vec<String>>,
+    /// e.g. ["lalt+f4","lalt+f9","lalt+lshift+f10"]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny_combos: Option<Vec<String>>,
+    /// Map of category -> disallowed modifiers, e.g. { "@ui_CCFPS": ["lctrl","lalt","lshift"] }
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disallowed_modifiers_per_category: Option<HashMap<String, Vec<String>>>,
+    /// Groups of categories that must not share binds, e.g.
+    /// [["@ui_CCSpaceFlight","@ui_CGUIGeneral"], ["@ui_CCFPS","@ui_CGUIGeneral"]]
+    /// (replaces `CATEGORY_GROUPS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_groups: Option<Vec<Vec<String>>>,
+    /// Map of action id -> activation mode for generated binds, e.g. { "spaceship_general.v_eject": "hold" }
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activation_policy: Option<HashMap<String, String>>,
+    /// Weights of the ergonomic cost, e.g. { "reach": 1.0, "modifier": 2.0, "right_modifier": 1.5, "proximity": 0.5 }
+    /// (missing fields keep their defaults)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_weights: Option<CostWeights>,
+    /// Most modifiers in one generated combo (default 2; 3 allows triples like lctrl+lalt+lshift)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_modifiers: Option<usize>,
+    /// Mouse buttons tried as main inputs, e.g. ["mouse4","mouse5"] (the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_buttons: Option<Vec<String>>,
+    /// Categories where mouse buttons are candidates, e.g. ["@ui_CCFPS"] (none by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_categories: Option<Vec<String>>,
+    /// Categories where wheel up/down are candidates, e.g. ["@ui_CCSpaceFlight"] (none by default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wheel_categories: Option<Vec<String>>,
+    /// Action ids (or "group:<name>") that get first pick of the pool, e.g.
+    /// ["spaceship_general.v_flightready"]; the plugin adds the actions placed on the deck
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_actions: Option<Vec<String>>,
+    /// Generate new binds for `priority_actions` only; other actions keep the binds the
+    /// previous run gave them (default false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_only: Option<bool>,
+}
+
+fn default_version() -> u32 {
+    BINDGEN_CONFIG_VERSION
+}
+
+impl Default for BindGenConfig {
+    fn default() -> Self {
+        Self {
+            version: BINDGEN_CONFIG_VERSION,
+            candidate_keys: None,
+            candidate_modifiers: None,
+            key_priority: None,
+            modifier_priority: None,
+            deny_combos: None,
+            disallowed_modifiers_per_category: None,
+            category_groups: None,
+            activation_policy: None,
+            cost_weights: None,
+            max_modifiers: None,
+            mouse_buttons: None,
+            mouse_categories: None,
+            wheel_categories: None,
+            priority_actions: None,
+            priority_only: None,
+        }
+    }
+}
+
+impl BindGenConfig {
+    /// Parse a config, rejecting versions newer than `BINDGEN_CONFIG_VERSION`.
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let cfg: BindGenConfig =
+            serde_json::from_str(content).map_err(|e| format!("parse bindgen config: {e}"))?;
+        if cfg.version > BINDGEN_CONFIG_VERSION {
+            return Err(format!(
+                "bindgen config version {} is newer than supported ({BINDGEN_CONFIG_VERSION})",
+                cfg.version
+            ));
+        }
+        Ok(cfg)
+    }
+
+    /// Read and parse a config file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+        Self::from_json(&content).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize bindgen config: {e}"))
+    }
+
+    /// Category → categories it must not share binds with: `category_groups` if set,
+    /// else `CATEGORY_GROUPS`.
+    pub fn category_group_map(&self) -> HashMap<String, HashSet<String>> {
+        match self.category_groups.as_ref() {
+            Some(groups) => group_map(groups),
+            None => CATEGORY_GROUPS.clone(),
+        }
+    }
+
+    /// Generator with this config applied over the defaults. Unknown tokens are skipped
+    /// with a warning.
+    ///
+    /// `priority_actions` are taken as plain action ids here;
+    /// `ActionBindings::generate_missing_binds_with` also expands "group:<name>" targets.
+    pub fn build(&self, modes: &ActivationArena, logger: &Arc<dyn CoreLog>) -> BindGenerator {
+        let mut generator = BindGenerator::with_logger(modes, logger);
+
+        if let Some(keys) = self.candidate_keys.as_ref() {
+            generator.available_keys = parse_key_list(keys, logger);
+        }
+        if let Some(mods) = self.candidate_modifiers.as_ref() {
+            generator.available_modifiers = parse_key_list(mods, logger);
+        }
+        // Priority: explicit list, else the order of the candidate list, else the built-in order
+        if let Some(keys) = self.key_priority.as_ref().or(self.candidate_keys.as_ref()) {
+            generator.key_priority = parse_key_order(keys, logger);
+        }
+        if let Some(mods) = self
+            .modifier_priority
+            .as_ref()
+            .or(self.candidate_modifiers.as_ref())
+        {
+            generator.modifier_priority = parse_key_order(mods, logger);
+        }
+        if let Some(combos) = self.deny_combos.as_ref() {
+            generator.banned_binds = parse_binds(combos, logger);
+        }
+        if let Some(m) = self.disallowed_modifiers_per_category.as_ref() {
+            generator.disallowed_modifiers = m
+                .iter()
+                .map(|(cat, keys)| (cat.clone(), parse_key_list(keys, logger)))
+                .collect();
+        }
+        if self.category_groups.is_some() {
+            generator.group_map = self.category_group_map();
+        }
+        if let Some(policy) = self.activation_policy.as_ref() {
+            generator.activation_policy = policy.clone();
+        }
+        if let Some(weights) = self.cost_weights {
+            generator.cost_weights = weights;
+        }
+        if let Some(depth) = self.max_modifiers {
+            generator.max_modifiers = depth;
+        }
+        if let Some(buttons) = self.mouse_buttons.as_ref() {
+            generator.mouse_buttons = parse_mouse_buttons(buttons, logger);
+        }
+        if let Some(cats) = self.mouse_categories.as_ref() {
+            generator.mouse_categories = cats.iter().cloned().collect();
+        }
+        if let Some(cats) = self.wheel_categories.as_ref() {
+            generator.wheel_categories = cats.iter().cloned().collect();
+        }
+        if let Some(ids) = self.priority_actions.as_ref() {
+            generator.priority_actions = ids.iter().map(intern).collect();
+        }
+        if let Some(only) = self.priority_only {
+            generator.priority_only = only;
+        }
+        generator
+    }
+}
+
+/// Category → every category it shares a group with (itself included).
+fn group_map(groups: &[Vec<String>]) -> HashMap<String, HashSet<String>> {
+    let mut map: HashMap<String, HashSet<String>> = HashMap::new();
+    for group in groups {
+        for cat in group {
+            map.entry(cat.clone())
+                .or_default()
+                .extend(group.iter().cloned());
+        }
+    }
+    map
+}
+
+fn parse_key_list(list: &[String], logger: &Arc<dyn CoreLog>) -> HashSet<Key> {
+    parse_key_order(list, logger).into_iter().collect()
+}
+
+fn parse_key_order(list: &[String], logger: &Arc<dyn CoreLog>) -> Vec<Key> {
+    let mut out = Vec::new();
+    for s in list {
+        match Key::parse(s) {
+            Some(k) if !out.contains(&k) => out.push(k),
+            Some(_) => {}
+            None => logger.warn(&format!("bindgen-config: unknown key '{s}' (skipped)")),
+        }
+    }
+    out
+}
+
+fn parse_binds(list: &[String], logger: &Arc<dyn CoreLog>) -> HashSet<Bind> {
+    let mut out = HashSet::new();
+    for s in list {
+        match Bind::from_string(s, None) {
+            Ok(b) => {
+                out.insert(b);
+            }
+            Err(_) => logger.warn(&format!("bindgen-config: bad bind '{s}' (skipped)")),
+        }
+    }
+    out
+}
+
+fn parse_mouse_buttons(list: &[String], logger: &Arc<dyn CoreLog>) -> Vec<MouseButton> {
+    let mut out = Vec::new();
+    for s in list {
+        match Bind::from_string(s, None).ok().and_then(|b| b.main) {
+            Some(BindMain::Mouse(btn)) if !out.contains(&btn) => out.push(btn),
+            Some(BindMain::Mouse(_)) => {}
+            _ => logger.warn(&format!("bindgen-config: bad mouse button '{s}' (skipped)")),
+        }
+    }
+    out
+}
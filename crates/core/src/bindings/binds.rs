@@ -1,13 +1,17 @@
 use serde::{Deserialize, Serialize};
 
 use crate::bindings::activation_mode::{ActivationArena, ActivationMode};
-use crate::bindings::bind::{Bind, BindMain, BindParseError};
+use crate::bindings::bind::{Bind, BindParseError, DeviceKind, DeviceRef};
 
-/// Keyboard/mouse binds for a single `<action>`.
+/// Per-device binds for a single `<action>`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Binds {
     pub keyboard: Vec<Bind>,
     pub mouse: Vec<Bind>,
+    #[serde(default)]
+    pub gamepad: Vec<Bind>,
+    #[serde(default)]
+    pub joystick: Vec<Bind>,
 }
 
 impl Binds {
@@ -19,19 +23,63 @@ impl Binds {
     /// True if any bind is present that isn’t explicitly unbound.
     #[inline]
     pub fn has_active_binds(&self) -> bool {
+        self.iter().any(|b| !b.is_unbound)
+    }
+
+    /// True if a keyboard or mouse bind is present that isn’t explicitly unbound.
+    #[inline]
+    pub fn has_active_kbm_binds(&self) -> bool {
         self.keyboard.iter().any(|b| !b.is_unbound) || self.mouse.iter().any(|b| !b.is_unbound)
     }
 
-    /// Iterate all binds by reference, keyboard first then mouse.
+    /// Binds for one device.
+    #[inline]
+    pub fn device(&self, kind: DeviceKind) -> &Vec<Bind> {
+        match kind {
+            DeviceKind::Keyboard => &self.keyboard,
+            DeviceKind::Mouse => &self.mouse,
+            DeviceKind::Gamepad => &self.gamepad,
+            DeviceKind::Joystick => &self.joystick,
+        }
+    }
+
+    /// Binds for one device, mutably.
+    #[inline]
+    pub fn device_mut(&mut self, kind: DeviceKind) -> &mut Vec<Bind> {
+        match kind {
+            DeviceKind::Keyboard => &mut self.keyboard,
+            DeviceKind::Mouse => &mut self.mouse,
+            DeviceKind::Gamepad => &mut self.gamepad,
+            DeviceKind::Joystick => &mut self.joystick,
+        }
+    }
+
+    /// Explicit device instances referenced by active binds (e.g. `js2` from "js2_button5").
+    #[inline]
+    pub fn device_refs(&self) -> impl Iterator<Item = DeviceRef> + '_ {
+        self.iter()
+            .filter(|b| !b.is_unbound)
+            .filter_map(|b| b.device)
+    }
+
+    /// Iterate all binds by reference: keyboard, mouse, gamepad, joystick.
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &Bind> {
-        self.keyboard.iter().chain(self.mouse.iter())
+        self.keyboard
+            .iter()
+            .chain(self.mouse.iter())
+            .chain(self.gamepad.iter())
+            .chain(self.joystick.iter())
     }
 
-    /// Iterate all binds mutably, keyboard first then mouse.
+    /// Iterate all binds mutably: keyboard, mouse, gamepad, joystick.
     #[inline]
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Bind> {
-        self.keyboard.iter_mut().chain(self.mouse.iter_mut())
+        self.keyboard
+            .iter_mut()
+            .chain(self.mouse.iter_mut())
+            .chain(self.gamepad.iter_mut())
+            .chain(self.joystick.iter_mut())
     }
 
     /// Return an owning iterator (clones).
@@ -50,13 +98,21 @@ impl Binds {
         self.mouse.push(b);
     }
 
+    /// Push to the device the bind's main input belongs to (explicit unbinds go to `fallback`).
+    #[inline]
+    pub fn push_routed(&mut self, b: Bind, fallback: DeviceKind) {
+        let kind = b.main.as_ref().map_or(fallback, |m| m.device_kind());
+        self.device_mut(kind).push(b);
+    }
+
     /// Parse binds for an `<action>` node, resolving activation modes into an arena (indices).
     ///
     /// Notes:
     /// - Explicit `unbound` entries are kept (`b.is_unbound == true`) so callers can distinguish
     ///   “explicitly clear this device” from “no change”.
-    /// - We still route wheel/axis/HMD tokens into the keyboard side by default (historical choice),
-    ///   except explicit mouse buttons which go to `mouse`.
+    /// - Mouse buttons, wheel and mouse axes land in `mouse` (wherever they were written), so
+    ///   they merge with `mo1_` rebinds; HMD tokens stay on the keyboard side.
+    /// - Gamepad/joystick values are parsed with their device context and land in `gamepad`/`joystick`.
     pub fn from_node(
         action_node: roxmltree::Node,
         activation_modes: &mut ActivationArena,
@@ -65,38 +121,32 @@ impl Binds {
         let mut errors = Vec::new();
 
         // Route *all* parsed binds, including explicit unbound, so the caller can tell intent.
-        let mut route = |b: Bind| match b.main {
-            Some(BindMain::Mouse(_)) => out.mouse.push(b),
-            _ => out.keyboard.push(b),
-        };
-
-        // ---- flat attributes: <action keyboard="..." mouse="..."> ----
-        parse_device_attr(
-            action_node,
-            "keyboard",
-            None,
-            activation_modes,
-            &mut route,
-            &mut errors,
-        );
-        parse_device_attr(
-            action_node,
-            "mouse",
-            None,
-            activation_modes,
-            &mut route,
-            &mut errors,
-        );
+        let mut route = |b: Bind, device: DeviceKind| out.push_routed(b, device);
+
+        // ---- flat attributes: <action keyboard="..." mouse="..." gamepad="..." joystick="..."> ----
+        for device in DeviceKind::ALL {
+            parse_device_attr(
+                action_node,
+                device.name(),
+                device,
+                None,
+                activation_modes,
+                &mut route,
+                &mut errors,
+            );
+        }
 
         // ---- nested device nodes: <action><keyboard input="..."><inputdata .../></keyboard> ... ----
-        for node in action_node
-            .children()
-            .filter(|n| n.is_element() && (n.has_tag_name("keyboard") || n.has_tag_name("mouse")))
-        {
+        for node in action_node.children().filter(|n| n.is_element()) {
+            let Some(device) = DeviceKind::from_name(node.tag_name().name()) else {
+                continue;
+            };
+
             // direct attribute on the device node
             parse_device_attr(
                 node,
                 "input",
+                device,
                 Some(action_node),
                 activation_modes,
                 &mut route,
@@ -116,8 +166,8 @@ impl Binds {
                 if let Some(raw) = input.attribute("input") {
                     let trimmed = raw.trim();
                     if !trimmed.is_empty() {
-                        match Bind::from_string(trimmed, mode) {
-                            Ok(b) => route(b),
+                        match Bind::from_device_string(trimmed, device, mode) {
+                            Ok(b) => route(b, device),
                             Err(e) => errors.push(e),
                         }
                     }
@@ -134,12 +184,13 @@ impl Binds {
 fn parse_device_attr<'a, F>(
     node: roxmltree::Node<'a, 'a>,
     attr: &str,
+    device: DeviceKind,
     parent_for_mode: Option<roxmltree::Node<'a, 'a>>,
     activation_modes: &mut ActivationArena,
     route: &mut F,
     errors: &mut Vec<BindParseError>,
 ) where
-    F: FnMut(Bind),
+    F: FnMut(Bind, DeviceKind),
 {
     if let Some(raw) = node.attribute(attr) {
         let trimmed = raw.trim();
@@ -152,8 +203,8 @@ fn parse_device_attr<'a, F>(
             parent_for_mode.and_then(|p| ActivationMode::resolve(p, None, activation_modes))
         });
 
-        match Bind::from_string(trimmed, mode) {
-            Ok(b) => route(b),
+        match Bind::from_device_string(trimmed, device, mode) {
+            Ok(b) => route(b, device),
             Err(e) => errors.push(e),
         }
     }
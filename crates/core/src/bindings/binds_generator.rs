@@ -8,34 +8,91 @@ use indexmap::IndexMap;
 use crate::{
     CoreLog,
     bindings::{
+        action_binding::ActionBinding,
+        action_group::ActionGroup,
         action_map::ActionMap,
         activation_mode::ActivationArena,
-        bind::{Bind, BindMain, Key},
+        assignment::min_cost_assignment,
+        bind::{Bind, BindMain, DeviceKind, Key, MouseButton},
+        bind_cost::{self, CostWeights},
+        bind_tokens::KeyTokenExt,
         binds::Binds,
         constants::{
-            CANDIDATE_KEYS, CANDIDATE_MODIFIERS, CATEGORY_GROUPS, DEFAULT_CATEGORY, DENY_COMBOS,
-            DENY_MODIFIERS_PER_CATEGORY,
+            CANDIDATE_KEY_ORDER, CANDIDATE_KEYS, CANDIDATE_MODIFIER_ORDER, CANDIDATE_MODIFIERS,
+            CATEGORY_GROUPS, DEFAULT_CATEGORY, DENY_COMBOS, DENY_MODIFIERS_PER_CATEGORY,
+            category_group,
         },
     },
 };
 
+/// Added per candidate position so equal costs resolve in priority order.
+const TIE_BREAK: f64 = 1e-6;
+
 /// Generates missing binds using available keys/modifiers and category rules.
 ///
+/// Candidates are ranked by an ergonomic cost (`cost_weights`, see `bind_cost`), and all
+/// unbound actions are assigned at once to minimize the total rather than first fit.
+///
+/// The result is deterministic, so identical inputs always yield identical binds:
+/// - actions are visited in profile order (action maps, then actions);
+/// - candidates are enumerated with main keys in `key_priority` order, then any remaining
+///   pool keys sorted by token, then `mouse_buttons` and the wheel; for each main input,
+///   modifier combos by size (none, singles, ... up to `max_modifiers`), each size
+///   ordered by `modifier_priority` (then token) lexicographically;
+/// - equal costs resolve to the earlier candidate in that order.
+///
 /// Core-friendly: no streamdeck-lib, no Windows. Logs via `CoreLog`.
 pub struct BindGenerator {
     pub available_keys: HashSet<Key>,
     pub available_modifiers: HashSet<Key>,
+    /// Preferred order of main keys (defaults to `CANDIDATE_KEY_ORDER`).
+    pub key_priority: Vec<Key>,
+    /// Preferred order of modifiers (defaults to `CANDIDATE_MODIFIER_ORDER`).
+    pub modifier_priority: Vec<Key>,
     pub banned_binds: HashSet<Bind>,
     pub group_map: HashMap<String, HashSet<String>>,
     pub disallowed_modifiers: HashMap<String, HashSet<Key>>,
+    /// Weights of the ergonomic cost that ranks candidates (see `bind_cost`).
+    pub cost_weights: CostWeights,
+    /// Most modifiers in one generated combo (default 2; 3 allows triples).
+    pub max_modifiers: usize,
+    /// Mouse buttons tried as main inputs (with modifiers) in `mouse_categories`
+    /// (default mouse4, mouse5).
+    pub mouse_buttons: Vec<MouseButton>,
+    /// Categories where `mouse_buttons` are candidates (none by default).
+    pub mouse_categories: HashSet<String>,
+    /// Categories where wheel up/down (without modifiers) are candidates (none by default).
+    pub wheel_categories: HashSet<String>,
     /// Arena index of the "press" activation mode (if present)
     pub press_idx: Option<usize>,
+    /// Activation mode name → arena index (for `activation_policy`).
+    mode_names: HashMap<String, usize>,
+    /// Explicit activation mode per action id (e.g. "spaceship_general.v_eject" → "hold").
+    /// Wins over the action's own mode.
+    pub activation_policy: HashMap<String, String>,
 
     /// Logger borrowed from caller (CLI or plugin adapter).
     pub logger: Arc<dyn CoreLog>,
 
-    /// Tracks used binds per group to avoid collisions.
-    used_binds_by_group: HashMap<String, HashSet<Bind>>,
+    /// Action groups whose members get one shared bind (see `ActionBindings::action_groups`).
+    pub action_groups: Vec<ActionGroup>,
+
+    /// Binds generated by a previous run, by action id (see
+    /// `ActionBindings::generated_binds`). An action that still needs a bind gets its old
+    /// one back if it is still allowed and free; only the rest go through the candidate order.
+    pub previous_binds: HashMap<Arc<str>, Bind>,
+
+    /// Action ids that get first pick of the pool (e.g. the actions placed on the deck).
+    pub priority_actions: HashSet<Arc<str>>,
+    /// Generate new binds for `priority_actions` only; other actions just keep their
+    /// `previous_binds`.
+    pub priority_only: bool,
+
+    /// Copy of the arena, to tell which activation modes can share a bind.
+    modes: ActivationArena,
+    /// Tracks used binds per group (with the activation modes they run with) to avoid
+    /// collisions.
+    used_binds_by_group: HashMap<String, HashMap<Bind, Vec<Option<usize>>>>,
 }
 
 impl BindGenerator {
@@ -53,15 +110,33 @@ impl BindGenerator {
             .iter()
             .find(|(_, am)| am.name.as_deref() == Some("press"))
             .map(|(idx, _)| idx);
+        let mode_names = modes
+            .iter()
+            .filter_map(|(idx, am)| am.name.clone().map(|n| (n, idx)))
+            .collect();
 
         Self {
             available_keys,
             available_modifiers,
+            key_priority: CANDIDATE_KEY_ORDER.clone(),
+            modifier_priority: CANDIDATE_MODIFIER_ORDER.clone(),
             banned_binds,
             group_map,
             disallowed_modifiers,
+            cost_weights: CostWeights::default(),
+            max_modifiers: 2,
+            mouse_buttons: vec![MouseButton::X(1), MouseButton::X(2)],
+            mouse_categories: HashSet::new(),
+            wheel_categories: HashSet::new(),
             press_idx,
+            mode_names,
+            activation_policy: HashMap::new(),
             logger: logger.clone(),
+            action_groups: Vec::new(),
+            previous_binds: HashMap::new(),
+            priority_actions: HashSet::new(),
+            priority_only: false,
+            modes: modes.clone(),
             used_binds_by_group: HashMap::new(),
         }
     }
@@ -126,7 +201,7 @@ impl BindGenerator {
         )
     }
 
-    /// Seed `used_binds_by_group` with existing binds.
+    /// Seed `used_binds_by_group` with existing binds and their activation modes.
     pub fn register_existing_binds(&mut self, action_maps: &IndexMap<Arc<str>, ActionMap>) {
         for action_map in action_maps.values() {
             let category = action_map
@@ -143,71 +218,230 @@ impl BindGenerator {
                 .unwrap_or_else(|| HashSet::from([category.clone()]));
 
             for binding in action_map.actions.values() {
-                let existing_iter =
-                    binding
-                        .default_binds
-                        .all_binds()
-                        .chain(binding.custom_binds.as_ref().map_or(
-                            Box::new(std::iter::empty()) as Box<dyn Iterator<Item = Bind>>,
-                            |cb| Box::new(cb.all_binds()),
-                        ));
-
-                let all_vec: Vec<Bind> = existing_iter.collect();
+                // Defaults the user rebound elsewhere don't hold their key anymore
+                let all_vec: Vec<Bind> = binding.effective_binds().all_binds().collect();
                 for g in &groups {
-                    self.used_binds_by_group
-                        .entry(g.clone())
-                        .or_default()
-                        .extend(all_vec.iter().cloned());
+                    let used = self.used_binds_by_group.entry(g.clone()).or_default();
+                    for b in &all_vec {
+                        used.entry(b.clone()).or_default().push(binding.mode_of(b));
+                    }
                 }
             }
         }
     }
 
-    /// Suggest the next unused bind for a category (respecting bans & group usage).
+    /// Suggest the cheapest unused bind for a category (respecting bans & group usage).
     pub fn next_available_bind(&mut self, category: &str) -> Option<Bind> {
-        let groups = self
-            .group_map
-            .get(category)
-            .cloned()
-            .unwrap_or_else(|| HashSet::from([category.to_string()]));
-
-        // Compute allowed modifier pool for this category.
-        let disallowed_mods = self.resolve_disallowed_modifiers(category);
-        let allowed_mods = self
-            .available_modifiers
-            .difference(&disallowed_mods)
-            .cloned()
-            .collect::<HashSet<_>>();
-
-        for key in &self.available_keys {
-            for mod_combo in Self::generate_modifier_combos(&allowed_mods) {
-                let candidate = Bind::generated(BindMain::Key(*key), mod_combo, self.press_idx);
-
-                if self.banned_binds.contains(&candidate) {
-                    continue;
-                }
+        self.next_available_bind_for(&[category])
+    }
 
-                // Used in any group?
-                let used = groups.iter().any(|g| {
-                    self.used_binds_by_group
-                        .get(g)
-                        .is_some_and(|s| s.contains(&candidate))
-                });
-                if used {
-                    continue;
-                }
+    /// Like `next_available_bind`, but the bind must be free in every listed category
+    /// (used for action groups whose members live in different maps). The bind is
+    /// checked as "press".
+    pub fn next_available_bind_for(&mut self, categories: &[&str]) -> Option<Bind> {
+        let rules = self.rules_for(categories, None, vec![self.press_idx]);
 
-                // Reserve in all groups and return.
-                for g in &groups {
-                    self.used_binds_by_group
-                        .entry(g.clone())
-                        .or_default()
-                        .insert(candidate.clone());
-                }
-                return Some(candidate);
+        // First of the cheapest wins, so equal costs fall back to priority order.
+        let (_, candidate) = self
+            .candidates()
+            .into_iter()
+            .filter_map(|c| Some((self.candidate_cost(&c, &rules)?, c)))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        self.reserve(&rules, &candidate);
+        Some(candidate)
+    }
+
+    /// Every candidate in priority order (see the struct docs): keys × modifier combos,
+    /// then mouse buttons × modifier combos, then the wheel (wheel binds take no modifiers).
+    fn candidates(&self) -> Vec<Bind> {
+        let mods = Self::ordered(&self.available_modifiers, &self.modifier_priority);
+        let combos = Self::generate_modifier_combos(&mods, self.max_modifiers);
+        let mains = Self::ordered(&self.available_keys, &self.key_priority)
+            .into_iter()
+            .map(BindMain::Key)
+            .chain(self.mouse_buttons.iter().map(|b| BindMain::Mouse(*b)));
+
+        let mut out: Vec<Bind> = mains
+            .flat_map(|main| {
+                combos
+                    .iter()
+                    .map(move |c| Bind::generated(main.clone(), c.clone(), self.press_idx))
+            })
+            .collect();
+        if !self.wheel_categories.is_empty() {
+            for main in [BindMain::MouseWheelUp, BindMain::MouseWheelDown] {
+                out.push(Bind::generated(main, HashSet::new(), self.press_idx));
+            }
+        }
+        out
+    }
+
+    /// Ergonomic cost of `candidate` under `rules`, or `None` if it is banned, already
+    /// used by a mode the unit's modes can't share with (`Bind::collides_with`), holds a
+    /// disallowed modifier, or is a mouse/wheel input the categories don't enable.
+    fn candidate_cost(&self, candidate: &Bind, rules: &UnitRules) -> Option<f64> {
+        let main = candidate.main.as_ref()?;
+        let enabled = match main {
+            BindMain::Key(_) => true,
+            BindMain::Mouse(_) => rules.mouse,
+            BindMain::MouseWheelUp | BindMain::MouseWheelDown => rules.wheel,
+            _ => false,
+        };
+        if !enabled
+            || self.banned_binds.contains(candidate)
+            || candidate
+                .modifiers
+                .iter()
+                .any(|m| rules.disallowed_mods.contains(m))
+            || self.is_used(rules, candidate)
+        {
+            return None;
+        }
+        Some(f64::from(self.cost_weights.cost(
+            main,
+            &candidate.modifiers,
+            rules.anchor,
+        )))
+    }
+
+    /// Reserve the bind `action_ids` had in the previous run, if any of them had one and
+    /// it is still allowed (pools, bans, per-category rules) and free in `categories` for
+    /// `modes`.
+    fn previous_bind_for(
+        &mut self,
+        action_ids: &[&str],
+        categories: &[&str],
+        modes: Vec<Option<usize>>,
+    ) -> Option<Bind> {
+        let prev = action_ids
+            .iter()
+            .find_map(|id| self.previous_binds.get(*id))?;
+        let main = prev.main.clone()?;
+
+        let in_pool = match &main {
+            BindMain::Key(k) => self.available_keys.contains(k),
+            BindMain::Mouse(b) => self.mouse_buttons.contains(b),
+            BindMain::MouseWheelUp | BindMain::MouseWheelDown => prev.modifiers.is_empty(),
+            _ => false,
+        } && prev.modifiers.len() <= self.max_modifiers
+            && prev
+                .modifiers
+                .iter()
+                .all(|m| self.available_modifiers.contains(m));
+        if !in_pool {
+            return None;
+        }
+
+        let rules = self.rules_for(categories, None, modes);
+        let candidate = Bind::generated(main, prev.modifiers.clone(), self.press_idx);
+        self.candidate_cost(&candidate, &rules)?;
+        self.reserve(&rules, &candidate);
+        Some(candidate)
+    }
+
+    /// What an action (or group) in `categories`, running with `modes`, may take.
+    fn rules_for(
+        &self,
+        categories: &[&str],
+        anchor: Option<(f32, f32)>,
+        modes: Vec<Option<usize>>,
+    ) -> UnitRules {
+        UnitRules {
+            groups: self.groups_for(categories),
+            modes,
+            disallowed_mods: self.disallowed_modifiers_for(categories),
+            anchor,
+            mouse: !self.mouse_buttons.is_empty()
+                && categories
+                    .iter()
+                    .all(|c| self.mouse_categories.contains(*c)),
+            wheel: categories
+                .iter()
+                .all(|c| self.wheel_categories.contains(*c)),
+        }
+    }
+
+    /// Category groups (see `group_map`) covering all `categories`.
+    fn groups_for(&self, categories: &[&str]) -> HashSet<String> {
+        categories
+            .iter()
+            .flat_map(|&c| category_group(&self.group_map, c))
+            .collect()
+    }
+
+    fn disallowed_modifiers_for(&self, categories: &[&str]) -> HashSet<Key> {
+        categories
+            .iter()
+            .flat_map(|c| self.resolve_disallowed_modifiers(c))
+            .collect()
+    }
+
+    /// Used in any of the unit's groups by a mode one of the unit's modes can't share
+    /// with (`Bind::collides_with`)?
+    fn is_used(&self, rules: &UnitRules, bind: &Bind) -> bool {
+        rules.groups.iter().any(|g| {
+            self.used_binds_by_group
+                .get(g)
+                .and_then(|used| used.get_key_value(bind))
+                .is_some_and(|(other, other_modes)| {
+                    other_modes.iter().any(|&o| {
+                        rules
+                            .modes
+                            .iter()
+                            .any(|&m| bind.collides_with(m, other, o, &self.modes))
+                    })
+                })
+        })
+    }
+
+    fn reserve(&mut self, rules: &UnitRules, bind: &Bind) {
+        for g in &rules.groups {
+            self.used_binds_by_group
+                .entry(g.clone())
+                .or_default()
+                .entry(bind.clone())
+                .or_default()
+                .extend(rules.modes.iter().copied());
+        }
+    }
+
+    /// Activation modes the actions of `unit` would run a generated bind with.
+    fn unit_modes(
+        &self,
+        action_maps: &IndexMap<Arc<str>, ActionMap>,
+        unit: &PendingUnit,
+    ) -> Vec<Option<usize>> {
+        let mut modes: Vec<Option<usize>> = unit
+            .members
+            .iter()
+            .map(|(map, action)| self.activation_mode_for(&action_maps[map].actions[action]))
+            .collect();
+        modes.dedup();
+        modes
+    }
+
+    /// Activation mode for a generated bind on `binding`:
+    /// policy → action-level mode → first default bind's mode → "press".
+    fn activation_mode_for(&self, binding: &ActionBinding) -> Option<usize> {
+        if let Some(name) = self.activation_policy.get(binding.action_id.as_ref()) {
+            match self.mode_names.get(name) {
+                Some(&idx) => return Some(idx),
+                None => self.logger.warn(&format!(
+                    "⚠️ Unknown activation mode '{name}' in policy for {}",
+                    binding.action_id
+                )),
             }
         }
-        None
+        binding
+            .activation_mode
+            .or_else(|| {
+                binding
+                    .default_binds
+                    .iter()
+                    .find_map(|b| b.activation_mode_idx)
+            })
+            .or(self.press_idx)
     }
 
     fn resolve_disallowed_modifiers(&self, category: &str) -> HashSet<Key> {
@@ -225,68 +459,348 @@ impl BindGenerator {
             .collect()
     }
 
-    /// Modifier combos: empty, singles, pairs.
-    fn generate_modifier_combos(mods: &HashSet<Key>) -> Vec<HashSet<Key>> {
-        let v: Vec<_> = mods.iter().copied().collect();
-        let mut out = Vec::new();
-
-        out.push(HashSet::new()); // empty (no modifiers)
-
-        for i in 0..v.len() {
-            let one = HashSet::from([v[i]]);
-            out.push(one.clone());
-
-            for j in v.iter().skip(i + 1) {
-                let mut two = one.clone();
-                two.insert(*j);
-                out.push(two);
+    /// `pool` in `priority` order, then the rest of `pool` sorted by token.
+    fn ordered(pool: &HashSet<Key>, priority: &[Key]) -> Vec<Key> {
+        let mut out: Vec<Key> = Vec::with_capacity(pool.len());
+        for k in priority {
+            if pool.contains(k) && !out.contains(k) {
+                out.push(*k);
             }
         }
+        let mut rest: Vec<Key> = pool.iter().filter(|k| !out.contains(k)).copied().collect();
+        rest.sort_by_key(|k| k.to_token());
+        out.extend(rest);
+        out
+    }
+
+    /// Modifier combos of up to `max` modifiers, by size (empty first), each size
+    /// lexicographic by `mods` order.
+    fn generate_modifier_combos(mods: &[Key], max: usize) -> Vec<HashSet<Key>> {
+        let mut out = vec![HashSet::new()]; // empty (no modifiers)
+        let mut layer: Vec<Vec<usize>> = vec![Vec::new()];
+
+        for _ in 0..max.min(mods.len()) {
+            // Extend each combo with every later modifier, keeping index order.
+            layer = layer
+                .iter()
+                .flat_map(|combo| {
+                    let start = combo.last().map_or(0, |i| i + 1);
+                    (start..mods.len()).map(move |i| {
+                        let mut next = combo.clone();
+                        next.push(i);
+                        next
+                    })
+                })
+                .collect();
+            out.extend(
+                layer
+                    .iter()
+                    .map(|combo| combo.iter().map(|&i| mods[i]).collect()),
+            );
+        }
         out
     }
 
     /// Fill gaps across all actions (custom > default).
+    ///
+    /// `priority_actions` are handled first, then everything else. Within each pass, binds
+    /// from `previous_binds` go back first and the remaining actions are assigned together
+    /// so the summed cost is minimal (see `assign_pending`). With `priority_only` the
+    /// other actions only get their previous binds back; nothing new is assigned to them.
     pub fn generate_missing_binds(&mut self, action_maps: &mut IndexMap<Arc<str>, ActionMap>) {
         self.register_existing_binds(action_maps);
 
-        for (map_name, action_map) in action_maps.iter_mut() {
+        let (first, rest): (Vec<_>, Vec<_>) = self
+            .pending_units(action_maps)
+            .into_iter()
+            .partition(|u| u.ids.iter().any(|id| self.priority_actions.contains(id)));
+        if !self.priority_actions.is_empty() {
+            self.logger.info(&format!(
+                "[generate_missing_binds] {} of {} priority actions need a bind",
+                first.len(),
+                self.priority_actions.len()
+            ));
+        }
+
+        let mut kept = 0usize;
+        for (units, assign) in [(first, true), (rest, !self.priority_only)] {
+            let mut pending = Vec::new();
+            for unit in units {
+                let ids: Vec<&str> = unit.ids.iter().map(|id| id.as_ref()).collect();
+                let categories: Vec<&str> = unit.categories.iter().map(String::as_str).collect();
+                let modes = self.unit_modes(action_maps, &unit);
+                match self.previous_bind_for(&ids, &categories, modes) {
+                    Some(bind) => {
+                        self.apply(action_maps, &unit, &bind);
+                        kept += 1;
+                    }
+                    None => pending.push(unit),
+                }
+            }
+            if assign {
+                self.assign_pending(action_maps, pending);
+            } else {
+                self.logger.info(&format!(
+                    "[generate_missing_binds] Skipping {} other unbound actions",
+                    pending.len()
+                ));
+            }
+        }
+        if !self.previous_binds.is_empty() {
+            self.logger.info(&format!(
+                "[generate_missing_binds] Kept {kept} of {} previously generated binds",
+                self.previous_binds.len()
+            ));
+        }
+
+        self.logger
+            .info("[generate_missing_binds] Done generating binds");
+    }
+
+    /// Only keyboard/mouse binds can be simulated, controller binds don't count.
+    fn needs_bind(binding: &ActionBinding) -> bool {
+        !binding.effective_binds().has_active_kbm_binds()
+    }
+
+    /// Everything that still needs a bind, in profile order: action groups first (one
+    /// unit per group with no bound member), then ungrouped actions.
+    fn pending_units(&self, action_maps: &IndexMap<Arc<str>, ActionMap>) -> Vec<PendingUnit> {
+        let mut units = Vec::new();
+        let mut grouped = HashSet::new();
+
+        for group in &self.action_groups {
+            let mut unit = PendingUnit {
+                label: format!("action group {}", group.name),
+                ..Default::default()
+            };
+            for id in &group.members {
+                let Some((map, action)) = id.split_once('.') else {
+                    continue;
+                };
+                let Some((map_name, am)) = action_maps.get_key_value(map) else {
+                    continue;
+                };
+                let Some((action_name, _)) = am.actions.get_key_value(action) else {
+                    continue;
+                };
+                let category = am.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+                unit.ids.push(id.clone());
+                unit.members.push((map_name.clone(), action_name.clone()));
+                if !unit.categories.iter().any(|c| c == category) {
+                    unit.categories.push(category.to_string());
+                }
+            }
+            if unit.members.is_empty() {
+                continue;
+            }
+            grouped.extend(group.members.iter().cloned());
+
+            let bound = unit
+                .members
+                .iter()
+                .any(|(map, action)| !Self::needs_bind(&action_maps[map].actions[action]));
+            if !bound {
+                unit.label = format!("{} ({} actions)", unit.label, unit.members.len());
+                units.push(unit);
+            }
+        }
+
+        for (map_name, action_map) in action_maps {
             let category = action_map
                 .ui_category
                 .as_deref()
-                .unwrap_or(DEFAULT_CATEGORY)
-                .to_string();
+                .unwrap_or(DEFAULT_CATEGORY);
+            for (action_name, binding) in &action_map.actions {
+                if grouped.contains(&binding.action_id) || !Self::needs_bind(binding) {
+                    continue;
+                }
+                units.push(PendingUnit {
+                    label: format!("{map_name}.{action_name}"),
+                    ids: vec![binding.action_id.clone()],
+                    members: vec![(map_name.clone(), action_name.clone())],
+                    categories: vec![category.to_string()],
+                });
+            }
+        }
 
-            for binding in action_map.actions.values_mut() {
-                let has_default = binding.default_binds.has_active_binds();
-                let has_custom = binding
-                    .custom_binds
-                    .as_ref()
-                    .is_some_and(|b| b.has_active_binds());
+        units
+    }
 
-                if has_default || has_custom {
-                    continue;
+    /// Assign binds to `pending` so the summed cost is minimal, instead of first fit.
+    ///
+    /// Units whose category groups overlap (directly or through other units) are solved
+    /// together and get distinct binds; each unit's proximity term uses the keys already
+    /// bound in its action maps.
+    fn assign_pending(
+        &mut self,
+        action_maps: &mut IndexMap<Arc<str>, ActionMap>,
+        pending: Vec<PendingUnit>,
+    ) {
+        if pending.is_empty() {
+            return;
+        }
+        let candidates = self.candidates();
+
+        let map_keys: HashMap<&Arc<str>, Vec<Key>> = action_maps
+            .iter()
+            .map(|(name, am)| {
+                let keys = am
+                    .actions
+                    .values()
+                    .flat_map(|a| a.effective_binds().keyboard)
+                    .filter_map(|b| match b.main {
+                        Some(BindMain::Key(k)) => Some(k),
+                        _ => None,
+                    })
+                    .collect();
+                (name, keys)
+            })
+            .collect();
+
+        // Per unit: what it may take, with the proximity anchor of its maps.
+        let prepared: Vec<UnitRules> = pending
+            .iter()
+            .map(|unit| {
+                let categories: Vec<&str> = unit.categories.iter().map(String::as_str).collect();
+                let maps: HashSet<&Arc<str>> = unit.members.iter().map(|(m, _)| m).collect();
+                let anchor = bind_cost::centroid(
+                    maps.into_iter()
+                        .flat_map(|m| map_keys.get(m).into_iter().flatten().copied()),
+                );
+                self.rules_for(&categories, anchor, self.unit_modes(action_maps, unit))
+            })
+            .collect();
+
+        let mut picks: Vec<Option<Bind>> = vec![None; pending.len()];
+        for component in Self::overlapping(prepared.iter().map(|r| &r.groups)) {
+            let costs: Vec<Vec<Option<f64>>> = component
+                .iter()
+                .map(|&u| {
+                    let rules = &prepared[u];
+                    candidates
+                        .iter()
+                        .enumerate()
+                        .map(|(j, c)| {
+                            let cost = self.candidate_cost(c, rules)?;
+                            // Equal costs fall back to priority order.
+                            Some(cost + j as f64 * TIE_BREAK)
+                        })
+                        .collect()
+                })
+                .collect();
+
+            for (&u, pick) in component.iter().zip(min_cost_assignment(&costs)) {
+                if let Some(j) = pick {
+                    self.reserve(&prepared[u], &candidates[j]);
+                    picks[u] = Some(candidates[j].clone());
                 }
+            }
+        }
 
-                if let Some(candidate) = self.next_available_bind(&category) {
-                    binding.custom_binds = Some(Binds {
-                        keyboard: vec![candidate.clone()],
-                        mouse: vec![],
-                    });
-
-                    self.logger.info(&format!(
-                        "✅ Generated bind for {}.{}: {}",
-                        map_name, binding.action_name, candidate
-                    ));
-                } else {
-                    self.logger.warn(&format!(
-                        "⚠️ No available bind for {}.{}",
-                        map_name, binding.action_name
-                    ));
+        for (unit, pick) in pending.iter().zip(picks) {
+            match pick {
+                Some(bind) => {
+                    self.apply(action_maps, unit, &bind);
+                    self.logger
+                        .info(&format!("✅ Generated bind for {}: {}", unit.label, bind));
+                }
+                None => {
+                    self.logger
+                        .warn(&format!("⚠️ No available bind for {}", unit.label));
                 }
             }
         }
+    }
 
-        self.logger
-            .info("[generate_missing_binds] Done generating binds");
+    /// Indices of `groups` split into components that share at least one group
+    /// (transitively), each in input order.
+    fn overlapping<'a>(groups: impl Iterator<Item = &'a HashSet<String>>) -> Vec<Vec<usize>> {
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let mut parent = Vec::new();
+        let mut owner: HashMap<&str, usize> = HashMap::new();
+        for (i, gs) in groups.enumerate() {
+            parent.push(i);
+            for g in gs {
+                match owner.get(g.as_str()) {
+                    Some(&o) => {
+                        let (a, b) = (root(&mut parent, o), root(&mut parent, i));
+                        parent[a.max(b)] = a.min(b);
+                    }
+                    None => {
+                        owner.insert(g, i);
+                    }
+                }
+            }
+        }
+
+        let mut components: IndexMap<usize, Vec<usize>> = IndexMap::new();
+        for i in 0..parent.len() {
+            let r = root(&mut parent, i);
+            components.entry(r).or_default().push(i);
+        }
+        components.into_values().collect()
     }
+
+    /// Give `bind` to every action of `unit`; each keeps its own activation mode.
+    fn apply(
+        &self,
+        action_maps: &mut IndexMap<Arc<str>, ActionMap>,
+        unit: &PendingUnit,
+        bind: &Bind,
+    ) {
+        // Mouse buttons and the wheel are written under the mouse device.
+        let device = match bind.main {
+            Some(BindMain::Key(_)) | None => DeviceKind::Keyboard,
+            _ => DeviceKind::Mouse,
+        };
+        for (map, action) in &unit.members {
+            if let Some(binding) = action_maps
+                .get_mut(map)
+                .and_then(|m| m.actions.get_mut(action))
+            {
+                let mut bind = bind.clone();
+                bind.activation_mode_idx = self.activation_mode_for(binding);
+                // Keep any custom controller rebinds next to the generated bind; it
+                // replaces an explicit unbind of its device.
+                let binds = binding
+                    .custom_binds
+                    .get_or_insert_with(Binds::new)
+                    .device_mut(device);
+                binds.retain(|b| !b.is_unbound);
+                binds.push(bind);
+            }
+        }
+    }
+}
+
+/// Actions that receive one generated bind: a single action, or every member of an
+/// action group.
+#[derive(Default)]
+struct PendingUnit {
+    /// For logs: "map.action" or "action group name (n actions)".
+    label: String,
+    ids: Vec<Arc<str>>,
+    /// (map, action) of each action.
+    members: Vec<(Arc<str>, Arc<str>)>,
+    categories: Vec<String>,
+}
+
+/// What a `PendingUnit` may take and where its related keys are.
+struct UnitRules {
+    groups: HashSet<String>,
+    /// Activation modes the unit's actions will run the bind with.
+    modes: Vec<Option<usize>>,
+    disallowed_mods: HashSet<Key>,
+    anchor: Option<(f32, f32)>,
+    /// Mouse-button candidates enabled in all of the unit's categories.
+    mouse: bool,
+    /// Wheel candidates enabled in all of the unit's categories.
+    wheel: bool,
 }
@@ -0,0 +1,226 @@
+//! Changelog between two game default profiles (e.g. LIVE vs a new PTU build).
+//!
+//! Renames are guessed: a removed action map pairs with an added one that has the same
+//! `UILabel` or keeps at least half of its action names; a removed action pairs with an
+//! added one that has the same name (moved to another map), else with one in its map's
+//! counterpart that has the same `UILabel` (only if no other action in either map has
+//! it: labels like `@ui_CIYaw` are reused across maps).
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    CoreLog,
+    bindings::{
+        action_binding::ActionBinding,
+        action_bindings::ActionBindings,
+        action_map::ActionMap,
+        constants::{ACTION_MAP_UI_CATEGORIES, SKIP_ACTION_MAPS},
+        profile_diff::{ActionDiff, action_diff},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Rename {
+    pub old: Arc<str>,
+    pub new: Arc<str>,
+}
+
+/// An action map whose `version` attribute went up. The game drops custom rebinds for a
+/// map when that happens.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VersionBump {
+    pub action_map: Arc<str>,
+    pub old: u32,
+    pub new: u32,
+}
+
+/// What changed between two default profiles, in profile order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DefaultsChangelog {
+    pub maps_added: Vec<Arc<str>>,
+    pub maps_removed: Vec<Arc<str>>,
+    pub maps_renamed: Vec<Rename>,
+    /// Under the new map name.
+    pub version_bumps: Vec<VersionBump>,
+    pub actions_added: Vec<Arc<str>>,
+    pub actions_removed: Vec<Arc<str>>,
+    /// Renamed or moved actions (not those that only followed their map's rename).
+    pub actions_renamed: Vec<Rename>,
+    /// Default binds of actions present on both sides (under the new id).
+    pub binds_changed: Vec<ActionDiff>,
+}
+
+impl DefaultsChangelog {
+    /// Compare two loaded default profiles (no custom profile applied).
+    pub fn between(old: &ActionBindings, new: &ActionBindings) -> Self {
+        let mut log = DefaultsChangelog::default();
+
+        // ── Action maps ──
+        let mut removed_maps: Vec<&ActionMap> = old
+            .action_maps
+            .values()
+            .filter(|m| !new.action_maps.contains_key(&m.name))
+            .collect();
+        let mut added_maps: Vec<&ActionMap> = new
+            .action_maps
+            .values()
+            .filter(|m| !old.action_maps.contains_key(&m.name))
+            .collect();
+
+        // old map name → new map name
+        let mut map_pairs: HashMap<&str, &ActionMap> = new
+            .action_maps
+            .values()
+            .filter(|m| old.action_maps.contains_key(&m.name))
+            .map(|m| (m.name.as_ref(), m))
+            .collect();
+        removed_maps.retain(|old_map| {
+            let Some(pos) = added_maps.iter().position(|m| same_map(old_map, m)) else {
+                return true;
+            };
+            let new_map = added_maps.remove(pos);
+            log.maps_renamed.push(Rename {
+                old: old_map.name.clone(),
+                new: new_map.name.clone(),
+            });
+            map_pairs.insert(&old_map.name, new_map);
+            false
+        });
+        log.maps_added = added_maps.iter().map(|m| m.name.clone()).collect();
+        log.maps_removed = removed_maps.iter().map(|m| m.name.clone()).collect();
+
+        log.version_bumps = old
+            .action_maps
+            .values()
+            .filter_map(|old_map| {
+                let new_map = map_pairs.get(old_map.name.as_ref())?;
+                (new_map.version > old_map.version).then(|| VersionBump {
+                    action_map: new_map.name.clone(),
+                    old: old_map.version,
+                    new: new_map.version,
+                })
+            })
+            .collect();
+
+        // ── Actions ──
+        // old action → its counterpart in the new profile
+        let mut pairs: Vec<(&ActionBinding, &ActionBinding)> = Vec::new();
+        let mut removed: Vec<(&ActionMap, &ActionBinding)> = Vec::new();
+        let mut matched: HashSet<&str> = HashSet::new();
+        for old_map in old.action_maps.values() {
+            let new_map = map_pairs.get(old_map.name.as_ref());
+            for action in old_map.actions.values() {
+                match new_map.and_then(|m| m.actions.get(&action.action_name)) {
+                    Some(counterpart) => {
+                        matched.insert(&counterpart.action_id);
+                        pairs.push((action, counterpart));
+                    }
+                    None => removed.push((old_map, action)),
+                }
+            }
+        }
+        let mut added: Vec<(&ActionMap, &ActionBinding)> = new
+            .action_maps
+            .values()
+            .flat_map(|m| m.actions.values().map(move |a| (m, a)))
+            .filter(|(_, a)| !matched.contains(a.action_id.as_ref()))
+            .collect();
+
+        removed.retain(|&(old_map, old_action)| {
+            let same_label = |(new_map, new_action): &(&ActionMap, &ActionBinding)| {
+                map_pairs
+                    .get(old_map.name.as_ref())
+                    .is_some_and(|m| m.name == new_map.name)
+                    && same_unique_label(old_map, old_action, new_map, new_action)
+            };
+            let pos = added
+                .iter()
+                .position(|(_, a)| a.action_name == old_action.action_name)
+                .or_else(|| added.iter().position(same_label));
+            let Some(pos) = pos else {
+                return true;
+            };
+            let (_, new_action) = added.remove(pos);
+            log.actions_renamed.push(Rename {
+                old: old_action.action_id.clone(),
+                new: new_action.action_id.clone(),
+            });
+            pairs.push((old_action, new_action));
+            false
+        });
+        log.actions_added = added.iter().map(|(_, a)| a.action_id.clone()).collect();
+        log.actions_removed = removed.iter().map(|(_, a)| a.action_id.clone()).collect();
+
+        // ── Default binds ──
+        log.binds_changed = pairs
+            .into_iter()
+            .filter_map(|(o, n)| action_diff(&n.action_id, Some(o), old, Some(n), new))
+            .collect();
+
+        log
+    }
+
+    /// Load both default profiles and compare them.
+    pub fn between_files(
+        old: &Path,
+        new: &Path,
+        logger: &Arc<dyn CoreLog>,
+    ) -> Result<Self, String> {
+        let load = |path: &Path| -> Result<ActionBindings, String> {
+            let mut ab = ActionBindings::default();
+            ab.load_default_profile(path, &SKIP_ACTION_MAPS, &ACTION_MAP_UI_CATEGORIES, logger)
+                .map_err(|e| format!("{}: {e}", path.display()))?
+                .log(logger);
+            Ok(ab)
+        };
+        Ok(Self::between(&load(old)?, &load(new)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == DefaultsChangelog::default()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize changelog: {e}"))
+    }
+}
+
+fn same_map(old: &ActionMap, new: &ActionMap) -> bool {
+    if old.ui_label.is_some() && old.ui_label == new.ui_label {
+        return true;
+    }
+    let kept = old
+        .actions
+        .keys()
+        .filter(|a| new.actions.contains_key(*a))
+        .count();
+    kept > 0 && kept * 2 >= old.actions.len()
+}
+
+/// Both actions carry the same `UILabel`, and no other action in their maps does.
+fn same_unique_label(
+    old_map: &ActionMap,
+    old: &ActionBinding,
+    new_map: &ActionMap,
+    new: &ActionBinding,
+) -> bool {
+    let Some(label) = old
+        .ui_label
+        .as_ref()
+        .filter(|l| new.ui_label.as_ref() == Some(*l))
+    else {
+        return false;
+    };
+    let uses = |map: &ActionMap| {
+        map.actions
+            .values()
+            .filter(|a| a.ui_label.as_ref() == Some(label))
+            .count()
+    };
+    uses(old_map) == 1 && uses(new_map) == 1
+}
@@ -0,0 +1,297 @@
+//! Printable reference of the binds the game will use: one section per action map,
+//! one row per action, rendered as CSV, Markdown or a standalone searchable HTML page.
+
+use std::{collections::HashMap, fmt::Write as _};
+
+use crate::bindings::{
+    action_binding::ActionBinding,
+    action_bindings::ActionBindings,
+    bind::{BindSource, DeviceKind},
+};
+
+/// Output format of `CheatSheet::render`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetFormat {
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl SheetFormat {
+    /// "csv" | "md"/"markdown" | "html"/"htm" (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Some(SheetFormat::Csv),
+            "md" | "markdown" => Some(SheetFormat::Markdown),
+            "html" | "htm" => Some(SheetFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// File extension without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SheetFormat::Csv => "csv",
+            SheetFormat::Markdown => "md",
+            SheetFormat::Html => "html",
+        }
+    }
+}
+
+/// Which actions make it onto the sheet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SheetOptions {
+    /// Skip actions without any bind.
+    pub only_bound: bool,
+    /// Only actions with at least one generated bind.
+    pub only_generated: bool,
+}
+
+/// One action on the sheet.
+#[derive(Debug, Clone)]
+pub struct SheetRow {
+    pub action_id: String,
+    /// Translated label, with the state labels for toggles ("Landing Gear (Deploy / Retract)").
+    pub label: String,
+    /// Effective binds, devices separated by " | " (same shape as `get_binds_label`);
+    /// empty if unbound.
+    pub binds: String,
+    /// Where those binds come from, sorted and deduped.
+    pub origins: Vec<BindSource>,
+}
+
+impl SheetRow {
+    /// Origins joined with "/", e.g. "default/generated".
+    pub fn origin_label(&self) -> String {
+        self.origins
+            .iter()
+            .map(BindSource::name)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// The rows of one action map.
+#[derive(Debug, Clone)]
+pub struct SheetSection {
+    pub action_map: String,
+    /// Translated map label (`ActionMap::get_label`).
+    pub title: String,
+    pub rows: Vec<SheetRow>,
+}
+
+/// Effective binds grouped by action map, in profile order. Maps left without rows
+/// by the filters are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct CheatSheet {
+    pub sections: Vec<SheetSection>,
+}
+
+impl CheatSheet {
+    pub fn build(
+        bindings: &ActionBindings,
+        translations: &HashMap<String, String>,
+        options: SheetOptions,
+    ) -> Self {
+        let sections = bindings
+            .action_maps
+            .values()
+            .filter_map(|map| {
+                let rows: Vec<SheetRow> = map
+                    .actions
+                    .values()
+                    .map(|action| row(action, translations))
+                    .filter(|r| !options.only_bound || !r.binds.is_empty())
+                    .filter(|r| {
+                        !options.only_generated || r.origins.contains(&BindSource::Generated)
+                    })
+                    .collect();
+                if rows.is_empty() {
+                    return None;
+                }
+                Some(SheetSection {
+                    action_map: map.name.to_string(),
+                    title: map.get_label(translations),
+                    rows,
+                })
+            })
+            .collect();
+        Self { sections }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn render(&self, format: SheetFormat, title: &str) -> String {
+        match format {
+            SheetFormat::Csv => self.to_csv(),
+            SheetFormat::Markdown => self.to_markdown(title),
+            SheetFormat::Html => self.to_html(title),
+        }
+    }
+
+    /// One line per action: `section,label,binds,origin,action_id` (RFC 4180 quoting).
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("section,label,binds,origin,action_id\n");
+        for section in &self.sections {
+            for r in &section.rows {
+                let fields = [
+                    section.title.as_str(),
+                    &r.label,
+                    &r.binds,
+                    &r.origin_label(),
+                    &r.action_id,
+                ];
+                let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                out.push_str(&line.join(","));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// A heading per action map, each with an Action | Binds | Origin table.
+    pub fn to_markdown(&self, title: &str) -> String {
+        let mut out = format!("# {}\n", md_cell(title));
+        for section in &self.sections {
+            let _ = write!(
+                out,
+                "\n## {}\n\n| Action | Binds | Origin |\n|---|---|---|\n",
+                md_cell(&section.title)
+            );
+            for r in &section.rows {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    md_cell(&r.label),
+                    md_cell(&r.binds),
+                    r.origin_label()
+                );
+            }
+        }
+        out
+    }
+
+    /// Self-contained page (inline CSS/JS, no external assets) with a search box that
+    /// filters rows by label, bind or action id.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut body = String::new();
+        for section in &self.sections {
+            let _ = write!(
+                body,
+                "<section>\n<h2>{}</h2>\n<table>\n<thead><tr><th>Action</th><th>Binds</th>\
+                 <th>Origin</th></tr></thead>\n<tbody>\n",
+                html_escape(&section.title)
+            );
+            for r in &section.rows {
+                let _ = writeln!(
+                    body,
+                    "<tr class=\"{}\" title=\"{}\"><td>{}</td><td><kbd>{}</kbd></td><td>{}</td></tr>",
+                    r.origins
+                        .iter()
+                        .map(BindSource::name)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    html_escape(&r.action_id),
+                    html_escape(&r.label),
+                    html_escape(&r.binds),
+                    r.origin_label()
+                );
+            }
+            body.push_str("</tbody>\n</table>\n</section>\n");
+        }
+
+        let title = html_escape(title);
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 1.5em; }}
+input {{ font-size: 1em; padding: .3em; width: 24em; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 1em; }}
+th, td {{ border: 1px solid #ccc; padding: .2em .5em; text-align: left; }}
+th {{ background: #eee; }}
+tr.custom td:last-child {{ color: #1565c0; }}
+tr.generated td:last-child {{ color: #2e7d32; }}
+kbd {{ font-family: monospace; }}
+@media print {{ input {{ display: none; }} section {{ break-inside: avoid; }} }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<input id="q" type="search" placeholder="Filter by action, bind or id" autofocus>
+{body}<script>
+document.getElementById("q").addEventListener("input", function () {{
+  var q = this.value.toLowerCase();
+  document.querySelectorAll("section").forEach(function (s) {{
+    var shown = 0;
+    s.querySelectorAll("tbody tr").forEach(function (tr) {{
+      var hit = (tr.textContent + " " + tr.title).toLowerCase().indexOf(q) >= 0;
+      tr.style.display = hit ? "" : "none";
+      if (hit) shown++;
+    }});
+    s.style.display = shown || s.querySelector("h2").textContent.toLowerCase().indexOf(q) >= 0 ? "" : "none";
+  }});
+}});
+</script>
+</body>
+</html>
+"#
+        )
+    }
+}
+
+fn row(action: &ActionBinding, translations: &HashMap<String, String>) -> SheetRow {
+    let mut label = action.get_label(translations);
+    if let Some(states) = action.get_states_label(translations) {
+        label = format!("{label} ({states})");
+    }
+
+    let mut per_device: Vec<(DeviceKind, Vec<String>)> = Vec::new();
+    let mut origins = Vec::new();
+    for (device, bind, source) in action.active_binds() {
+        match per_device.last_mut() {
+            Some((d, binds)) if *d == device => binds.push(bind.to_string()),
+            _ => per_device.push((device, vec![bind.to_string()])),
+        }
+        origins.push(source);
+    }
+    origins.sort();
+    origins.dedup();
+
+    SheetRow {
+        action_id: action.action_id.to_string(),
+        label,
+        binds: per_device
+            .into_iter()
+            .map(|(_, binds)| binds.join(", "))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        origins,
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn md_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape text for HTML/XML content and double-quoted attributes (also used for the
+/// heatmap SVG).
+pub(crate) fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,190 @@
+//! Conflict analysis: one bind shared by two actions in overlapping categories.
+//!
+//! Uses the generator's notion of a collision (see `BindGenerator::register_existing_binds`):
+//! each action map's category expands to its category group (`CATEGORY_GROUPS`, or a
+//! config's `category_groups`), and two actions collide when their expansions overlap
+//! and they have an equal keyboard/mouse bind whose activation modes would fire on the
+//! same gesture (`Bind::collides_with`: a tap and a later hold may share a key). Members
+//! of one action group share their bind on purpose and are never reported against each
+//! other.
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::bindings::{
+    action_binding::ActionBinding,
+    action_bindings::ActionBindings,
+    bind::{Bind, BindSource, DeviceKind},
+    constants::{DEFAULT_CATEGORY, category_group},
+};
+
+/// One of the two actions in a conflict.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConflictSide {
+    pub action_id: Arc<str>,
+    /// UI category of the action's map (`DEFAULT_CATEGORY` if it has none).
+    pub category: String,
+    pub origin: BindSource,
+    /// Activation mode of the bind, else of the action (`None` if neither sets one).
+    pub activation_mode: Option<String>,
+}
+
+/// Two actions that react to the same bind in overlapping categories.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BindConflict {
+    /// The shared bind, e.g. "lalt+f5".
+    pub bind: String,
+    pub device: DeviceKind,
+    /// Categories both sides' groups have in common, sorted.
+    pub group: Vec<String>,
+    /// The side that comes first in profile order.
+    pub first: ConflictSide,
+    pub second: ConflictSide,
+}
+
+impl BindConflict {
+    /// Both sides come straight from the game's default profile.
+    pub fn is_default_only(&self) -> bool {
+        self.first.origin == BindSource::Default && self.second.origin == BindSource::Default
+    }
+}
+
+/// Every conflict in a set of bindings, in profile order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConflictReport {
+    pub conflicts: Vec<BindConflict>,
+}
+
+/// A keyboard/mouse bind in use by one action.
+struct Entry<'a> {
+    binding: &'a ActionBinding,
+    category: &'a str,
+    groups: &'a HashSet<String>,
+    device: DeviceKind,
+    origin: BindSource,
+    /// Arena index of the bind's activation mode, else the action's.
+    mode: Option<usize>,
+    activation_mode: Option<String>,
+}
+
+impl ConflictReport {
+    /// Analyze `bindings`; `group_map` is category → categories it must not share binds
+    /// with (the shape of `CATEGORY_GROUPS`). Categories missing from it only clash
+    /// with themselves.
+    pub fn analyze(
+        bindings: &ActionBindings,
+        group_map: &HashMap<String, HashSet<String>>,
+    ) -> Self {
+        let expansions: HashMap<&str, HashSet<String>> = bindings
+            .action_maps
+            .values()
+            .map(|m| {
+                let cat = m.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+                (cat, category_group(group_map, cat))
+            })
+            .collect();
+
+        // action id → action groups it belongs to
+        let mut action_groups: HashMap<&str, Vec<&str>> = HashMap::new();
+        for group in bindings.action_groups.values() {
+            for member in &group.members {
+                action_groups
+                    .entry(member.as_ref())
+                    .or_default()
+                    .push(group.name.as_ref());
+            }
+        }
+
+        let mut by_bind: IndexMap<&Bind, Vec<Entry>> = IndexMap::new();
+        for map in bindings.action_maps.values() {
+            let category = map.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+            let groups = &expansions[category];
+            for binding in map.actions.values() {
+                let kbm = binding
+                    .active_binds()
+                    .filter(|(d, ..)| matches!(d, DeviceKind::Keyboard | DeviceKind::Mouse));
+                for (device, bind, origin) in kbm {
+                    let activation_mode = binding.mode_name(bind, &bindings.activation);
+                    by_bind.entry(bind).or_default().push(Entry {
+                        binding,
+                        category,
+                        groups,
+                        device,
+                        origin,
+                        mode: binding.mode_of(bind),
+                        activation_mode,
+                    });
+                }
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        for (bind, entries) in &by_bind {
+            for (i, a) in entries.iter().enumerate() {
+                for b in &entries[i + 1..] {
+                    let (a_id, b_id) = (&a.binding.action_id, &b.binding.action_id);
+                    // Same key, but e.g. a tap and a later hold: both work
+                    if a_id == b_id || bindings.activation.can_share(a.mode, b.mode) {
+                        continue;
+                    }
+                    let same_group = action_groups.get(a_id.as_ref()).is_some_and(|ga| {
+                        action_groups
+                            .get(b_id.as_ref())
+                            .is_some_and(|gb| ga.iter().any(|g| gb.contains(g)))
+                    });
+                    if same_group {
+                        continue;
+                    }
+                    let mut group: Vec<String> = a.groups.intersection(b.groups).cloned().collect();
+                    if group.is_empty() {
+                        continue;
+                    }
+                    group.sort_unstable();
+
+                    conflicts.push(BindConflict {
+                        bind: bind.to_string(),
+                        device: a.device,
+                        group,
+                        first: a.side(),
+                        second: b.side(),
+                    });
+                }
+            }
+        }
+
+        ConflictReport { conflicts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.conflicts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Conflicts with a custom or generated side (overlaps within the game's own
+    /// defaults are usually intended).
+    pub fn user_conflicts(&self) -> impl Iterator<Item = &BindConflict> {
+        self.conflicts.iter().filter(|c| !c.is_default_only())
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize conflicts: {e}"))
+    }
+}
+
+impl Entry<'_> {
+    fn side(&self) -> ConflictSide {
+        ConflictSide {
+            action_id: self.binding.action_id.clone(),
+            category: self.category.to_string(),
+            origin: self.origin,
+            activation_mode: self.activation_mode.clone(),
+        }
+    }
+}
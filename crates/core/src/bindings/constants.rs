@@ -37,27 +37,32 @@ pub static ACTION_MAP_UI_CATEGORIES: Lazy<HashMap<String, String>> = Lazy::new(|
     .collect()
 });
 
-/// Pool of candidate main keys for auto-generation.
-pub static CANDIDATE_KEYS: Lazy<HashSet<Key>> = Lazy::new(|| {
+/// Candidate main keys for auto-generation, in generator priority order
+/// (function keys, numpad, digits, navigation, right-hand letters, arrows, punctuation).
+pub static CANDIDATE_KEY_ORDER: Lazy<Vec<Key>> = Lazy::new(|| {
     use Key::*;
-    [
+    vec![
         F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, Np0, Np1, Np2, Np3, Np4, Np5, Np6, Np7,
         Np8, Np9, NpAdd, NpSubtract, NpMultiply, NpDivide, NpDecimal, D0, D1, D2, D3, D4, D5, D6,
         D7, D8, D9, Insert, Delete, Home, End, PageUp, PageDown, U, I, O, P, J, K, L, ArrowUp,
         ArrowDown, ArrowLeft, ArrowRight, Semicolon, Comma, Period, Slash, Backslash, Minus, Equal,
     ]
-    .into_iter()
-    .collect()
 });
 
-/// Pool of candidate modifier keys for auto-generation.
-pub static CANDIDATE_MODIFIERS: Lazy<HashSet<Key>> = Lazy::new(|| {
+/// Pool of candidate main keys for auto-generation.
+pub static CANDIDATE_KEYS: Lazy<HashSet<Key>> =
+    Lazy::new(|| CANDIDATE_KEY_ORDER.iter().copied().collect());
+
+/// Candidate modifier keys, in generator priority order.
+pub static CANDIDATE_MODIFIER_ORDER: Lazy<Vec<Key>> = Lazy::new(|| {
     use Key::*;
-    [LShift, RShift, LCtrl, RCtrl, LAlt, RAlt]
-        .into_iter()
-        .collect()
+    vec![LShift, RShift, LCtrl, RCtrl, LAlt, RAlt]
 });
 
+/// Pool of candidate modifier keys for auto-generation.
+pub static CANDIDATE_MODIFIERS: Lazy<HashSet<Key>> =
+    Lazy::new(|| CANDIDATE_MODIFIER_ORDER.iter().copied().collect());
+
 /// Binds we should never generate (reserved/OS-dangerous combos).
 pub static DENY_COMBOS: Lazy<HashSet<Bind>> = Lazy::new(|| {
     use Key::*;
@@ -144,3 +149,15 @@ pub static CATEGORY_GROUPS: Lazy<HashMap<String, HashSet<String>>> = Lazy::new(|
 
     map
 });
+
+/// `category`'s group in `group_map` (the shape of `CATEGORY_GROUPS`), or just itself if
+/// it isn't in one.
+pub fn category_group(
+    group_map: &HashMap<String, HashSet<String>>,
+    category: &str,
+) -> HashSet<String> {
+    group_map
+        .get(category)
+        .cloned()
+        .unwrap_or_else(|| HashSet::from([category.to_string()]))
+}
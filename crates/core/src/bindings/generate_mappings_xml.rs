@@ -5,13 +5,23 @@ use std::io::BufWriter;
 use std::path::Path;
 
 use crate::bindings::action_bindings::ActionBindings;
-use crate::bindings::bind::BindOrigin;
-use crate::bindings::bind_tokens::bind_to_input_with_prefix;
+use crate::bindings::bind::{BindOrigin, DeviceKind, DeviceRef};
+use crate::bindings::bind_tokens::{DeviceInstances, bind_to_input_with_prefix};
+use crate::bindings::passthrough::XmlElement;
 
 impl ActionBindings {
     /// Emit a Star Citizen mappings XML containing **only** actions that have active custom binds.
     ///
-    /// - `devices`: optional list of (`"keyboard"|"mouse"`, instance_id_str). Defaults to `keyboard=1, mouse=1`.
+    /// Content preserved from the applied custom profile (`self.passthrough`) is written back
+    /// as-is: top-level `<options>`/`<deviceoptions>`, unknown attributes,
+    /// unparsed rebinds and actions that aren't in the loaded defaults. The user's declared
+    /// `<modifiers>` are written back from `custom_modifiers`. Option groups edited through
+    /// `option_group_mut` replace their entry in every `<options>` block of their device
+    /// type (a new `instance="1"` block if the custom profile had none).
+    ///
+    /// - `devices`: optional list of (`"keyboard"|"mouse"|"gamepad"|"joystick"`, instance_id_str).
+    ///   Defaults to `keyboard=1, mouse=1`. Instances referenced by custom binds (e.g. `js2_`)
+    ///   are added when missing; unprefixed gamepad/joystick binds add instance 1.
     /// - `profile_name`: written into `<ActionMaps profileName="">` and `<CustomisationUIHeader label="">`.
     pub fn generate_mapping_xml<P: AsRef<Path>>(
         &self,
@@ -19,6 +29,8 @@ impl ActionBindings {
         devices: Option<&[(&str, &str)]>,
         profile_name: &str,
     ) -> Result<(), String> {
+        let elements = self.top_level_elements();
+
         // ── writer setup ─────────────────────────────────────────────────────────
         let file = File::create(&output_path)
             .map_err(|e| format!("create {}: {e}", output_path.as_ref().display()))?;
@@ -35,6 +47,9 @@ impl ActionBindings {
         root.push_attribute(("optionsVersion", "2"));
         root.push_attribute(("rebindVersion", "2"));
         root.push_attribute(("profileName", profile_name));
+        for (k, v) in &self.passthrough.root_attrs {
+            root.push_attribute((k.as_str(), v.as_str()));
+        }
         writer
             .write_event(Event::Start(root))
             .map_err(|e| format!("<ActionMaps>: {e}"))?;
@@ -54,23 +69,78 @@ impl ActionBindings {
             .map_err(|e| format!("<devices>: {e}"))?;
 
         let default_devices = [("keyboard", "1"), ("mouse", "1")];
-        let dev_list = devices.unwrap_or(&default_devices);
-
-        // Resolve instance ids we’ll use in the rebind "input" strings
-        let kb_inst = dev_list
+        let mut dev_list: Vec<(&str, String)> = devices
+            .unwrap_or(&default_devices)
             .iter()
-            .find(|(t, _)| *t == "keyboard")
-            .map(|(_, i)| *i)
-            .unwrap_or("1");
-        let mo_inst = dev_list
-            .iter()
-            .find(|(t, _)| *t == "mouse")
-            .map(|(_, i)| *i)
-            .unwrap_or("1");
+            .map(|&(t, i)| (t, i.to_string()))
+            .collect();
+
+        // Every device instance a custom bind was read from must be declared too.
+        let custom_binds = || {
+            self.action_maps
+                .values()
+                .flat_map(|am| am.actions.values())
+                .filter_map(|a| a.custom_binds.as_ref())
+        };
+        let mut used: Vec<DeviceRef> = custom_binds().flat_map(|b| b.device_refs()).collect();
 
-        for &(dev_type, instance) in dev_list {
-            let mut dev = BytesStart::new(dev_type);
-            dev.push_attribute(("instance", instance));
+        // Controllers without an explicit instance fall back to instance 1.
+        for kind in [DeviceKind::Gamepad, DeviceKind::Joystick] {
+            let listed = dev_list.iter().any(|(t, _)| *t == kind.name());
+            let unprefixed = custom_binds().any(|b| {
+                b.device(kind)
+                    .iter()
+                    .any(|b| !b.is_unbound && b.device.is_none())
+            });
+            if unprefixed && !listed {
+                used.push(DeviceRef::new(kind, 1));
+            }
+        }
+
+        // Devices with preserved <options> blocks stay declared.
+        for opt in elements.iter().filter(|e| e.name == "options") {
+            let kind = opt.attr("type").and_then(DeviceKind::from_name);
+            let instance = opt.attr("instance").and_then(|i| i.parse::<u8>().ok());
+            if let Some((kind, instance)) = kind.zip(instance) {
+                used.push(DeviceRef::new(kind, instance));
+            }
+        }
+
+        used.sort_by_key(|d| {
+            (
+                DeviceKind::ALL.iter().position(|k| *k == d.kind),
+                d.instance,
+            )
+        });
+        used.dedup();
+        for d in used {
+            let instance = d.instance.to_string();
+            let listed = dev_list
+                .iter()
+                .any(|(t, i)| *t == d.kind.name() && *i == instance);
+            if !listed {
+                dev_list.push((d.kind.name(), instance));
+            }
+        }
+
+        // Resolve fallback instance ids for binds without an explicit device
+        let inst_of = |kind: DeviceKind| {
+            dev_list
+                .iter()
+                .find(|(t, _)| *t == kind.name())
+                .map(|(_, i)| i.as_str())
+                .unwrap_or("1")
+        };
+        let instances = DeviceInstances {
+            keyboard: inst_of(DeviceKind::Keyboard),
+            mouse: inst_of(DeviceKind::Mouse),
+            gamepad: inst_of(DeviceKind::Gamepad),
+            joystick: inst_of(DeviceKind::Joystick),
+        };
+
+        for (dev_type, instance) in &dev_list {
+            let mut dev = BytesStart::new(*dev_type);
+            dev.push_attribute(("instance", instance.as_str()));
             writer
                 .write_event(Event::Empty(dev))
                 .map_err(|e| format!("device <{dev_type}>: {e}"))?;
@@ -83,77 +153,148 @@ impl ActionBindings {
             .write_event(Event::End(BytesEnd::new("CustomisationUIHeader")))
             .map_err(|e| format!("</CustomisationUIHeader>: {e}"))?;
 
-        // ── <modifiers/> (kept empty) ────────────────────────────────────────────
-        writer
-            .write_event(Event::Empty(BytesStart::new("modifiers")))
-            .map_err(|e| format!("<modifiers>: {e}"))?;
+        // ── preserved top-level elements (<options>, <deviceoptions>, ...) ────────
+        for elem in &elements {
+            elem.write(&mut writer)?;
+        }
+
+        // ── <modifiers> (the user's declared modifiers, else empty) ──────────────
+        let declared = self.custom_modifiers.as_deref().unwrap_or_default();
+        if declared.is_empty() {
+            writer
+                .write_event(Event::Empty(BytesStart::new("modifiers")))
+                .map_err(|e| format!("<modifiers>: {e}"))?;
+        } else {
+            writer
+                .write_event(Event::Start(BytesStart::new("modifiers")))
+                .map_err(|e| format!("<modifiers>: {e}"))?;
+            for input in declared {
+                let mut m = BytesStart::new("mod");
+                m.push_attribute(("input", input.as_ref()));
+                writer
+                    .write_event(Event::Empty(m))
+                    .map_err(|e| format!("<mod input=\"{input}\">: {e}"))?;
+            }
+            writer
+                .write_event(Event::End(BytesEnd::new("modifiers")))
+                .map_err(|e| format!("</modifiers>: {e}"))?;
+        }
+
+        // ── actionmaps with actual custom binds (or preserved content) ───────────
+        let passthrough_only = self
+            .passthrough
+            .unknown_actions
+            .keys()
+            .filter(|name| !self.action_maps.contains_key(name.as_str()))
+            .map(String::as_str);
+        let map_names: Vec<&str> = self
+            .action_maps
+            .keys()
+            .map(|k| k.as_ref())
+            .chain(passthrough_only)
+            .collect();
+
+        for map_name in map_names {
+            let action_map = self.action_maps.get(map_name);
 
-        // ── actionmaps with actual custom binds ──────────────────────────────────
-        for (map_name, action_map) in &self.action_maps {
             // Only write an actionmap if it has at least one action with active custom binds
+            // or something preserved from the custom profile
             let custom_actions: Vec<_> = action_map
-                .actions
-                .values()
-                .filter(|binding| {
-                    binding
-                        .custom_binds
-                        .as_ref()
-                        .is_some_and(|b| b.has_active_binds())
+                .into_iter()
+                .flat_map(|am| am.actions.values())
+                .filter_map(|binding| {
+                    // Explicit unbinds read from the custom profile count too (they clear defaults)
+                    let custom = binding.custom_binds.as_ref().filter(|b| {
+                        b.has_active_binds() || b.iter().any(|b| b.is_unbound && b.device.is_some())
+                    });
+                    let extra = self
+                        .passthrough
+                        .actions
+                        .get(&format!("{map_name}.{}", binding.action_name));
+                    (custom.is_some() || extra.is_some()).then_some((binding, custom, extra))
                 })
                 .collect();
+            let unknown_actions = self
+                .passthrough
+                .unknown_actions
+                .get(map_name)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
 
-            if custom_actions.is_empty() {
+            if custom_actions.is_empty() && unknown_actions.is_empty() {
                 continue;
             }
 
             let mut am = BytesStart::new("actionmap");
-            am.push_attribute(("name", map_name.as_ref()));
+            am.push_attribute(("name", map_name));
+            for (k, v) in self
+                .passthrough
+                .actionmap_attrs
+                .get(map_name)
+                .into_iter()
+                .flatten()
+            {
+                am.push_attribute((k.as_str(), v.as_str()));
+            }
             writer
                 .write_event(Event::Start(am))
                 .map_err(|e| format!("<actionmap name=\"{map_name}\">: {e}"))?;
 
-            for action in custom_actions {
-                let custom = action.custom_binds.as_ref().expect("checked above");
-
+            for (action, custom, extra) in custom_actions {
                 let mut action_elem = BytesStart::new("action");
                 action_elem.push_attribute(("name", action.action_name.as_ref()));
+                for (k, v) in extra.into_iter().flat_map(|x| &x.attrs) {
+                    action_elem.push_attribute((k.as_str(), v.as_str()));
+                }
                 writer
                     .write_event(Event::Start(action_elem))
                     .map_err(|e| format!("<action name=\"{}\">: {e}", action.action_name))?;
 
-                // Keyboard rebinds
-                for bind in &custom.keyboard {
-                    if let Some(input_val) =
-                        bind_to_input_with_prefix(&bind.main, &bind.modifiers, kb_inst, mo_inst)
-                    {
-                        let mut rebind = BytesStart::new("rebind");
-                        rebind.push_attribute(("device", "keyboard"));
-                        if bind.origin == BindOrigin::Generated {
-                            // Generated binds default to "press" unless caller set a specific mode on the bind
-                            rebind.push_attribute(("activationMode", "press"));
+                // Rebinds per device (keyboard, mouse, gamepad, joystick)
+                for device in DeviceKind::ALL {
+                    for bind in custom.into_iter().flat_map(|c| c.device(device)) {
+                        let input_val = if bind.is_unbound {
+                            bind.device.map(|d| format!("{} ", d.prefix()))
+                        } else {
+                            bind_to_input_with_prefix(bind, &instances)
+                        };
+                        if let Some(input_val) = input_val {
+                            let mut rebind = BytesStart::new("rebind");
+                            rebind.push_attribute(("device", device.name()));
+                            // Named modes by name, unnamed (inline) modes as attributes
+                            let mode = bind
+                                .activation_mode_idx
+                                .and_then(|ix| self.activation.get(ix));
+                            match mode {
+                                Some(m) => {
+                                    if let Some(name) = m.name.as_deref() {
+                                        rebind.push_attribute(("activationMode", name));
+                                    }
+                                }
+                                None if bind.origin == BindOrigin::Generated => {
+                                    rebind.push_attribute(("activationMode", "press"));
+                                }
+                                None => {}
+                            }
+                            rebind.push_attribute(("input", input_val.as_str()));
+                            if let Some(m) = mode.filter(|m| m.name.is_none()) {
+                                for (k, v) in m.to_attrs() {
+                                    rebind.push_attribute((k, v.as_str()));
+                                }
+                            }
+                            for (k, v) in &bind.extra_attrs {
+                                rebind.push_attribute((k.as_str(), v.as_str()));
+                            }
+                            writer
+                                .write_event(Event::Empty(rebind))
+                                .map_err(|e| format!("{device} rebind: {e}"))?;
                         }
-                        rebind.push_attribute(("input", input_val.as_str()));
-                        writer
-                            .write_event(Event::Empty(rebind))
-                            .map_err(|e| format!("keyboard rebind: {e}"))?;
                     }
                 }
 
-                // Mouse rebinds
-                for bind in &custom.mouse {
-                    if let Some(input_val) =
-                        bind_to_input_with_prefix(&bind.main, &bind.modifiers, kb_inst, mo_inst)
-                    {
-                        let mut rebind = BytesStart::new("rebind");
-                        rebind.push_attribute(("device", "mouse"));
-                        if bind.origin == BindOrigin::Generated {
-                            rebind.push_attribute(("activationMode", "press"));
-                        }
-                        rebind.push_attribute(("input", input_val.as_str()));
-                        writer
-                            .write_event(Event::Empty(rebind))
-                            .map_err(|e| format!("mouse rebind: {e}"))?;
-                    }
+                // Unparsed rebinds and unknown children, unchanged
+                for child in extra.into_iter().flat_map(|x| &x.children) {
+                    child.write(&mut writer)?;
                 }
 
                 writer
@@ -161,6 +302,11 @@ impl ActionBindings {
                     .map_err(|e| format!("</action>: {e}"))?;
             }
 
+            // Actions we don't load, unchanged
+            for action in unknown_actions {
+                action.write(&mut writer)?;
+            }
+
             writer
                 .write_event(Event::End(BytesEnd::new("actionmap")))
                 .map_err(|e| format!("</actionmap>: {e}"))?;
@@ -173,4 +319,63 @@ impl ActionBindings {
 
         Ok(())
     }
+
+    /// Preserved top-level elements with edited option groups merged into `<options>`.
+    fn top_level_elements(&self) -> Vec<XmlElement> {
+        let mut elements = self.passthrough.elements.clone();
+
+        for tree in &self.options {
+            let edited = tree.edited_groups();
+            if edited.is_empty() {
+                continue;
+            }
+            let ty = tree.device.name();
+            let is_block = |e: &XmlElement| e.name == "options" && e.attr("type") == Some(ty);
+            if !elements.iter().any(is_block) {
+                let at = elements
+                    .iter()
+                    .rposition(|e| e.name == "options")
+                    .map_or(0, |i| i + 1);
+                elements.insert(
+                    at,
+                    XmlElement {
+                        name: "options".to_string(),
+                        attrs: vec![
+                            ("type".to_string(), ty.to_string()),
+                            ("instance".to_string(), "1".to_string()),
+                        ],
+                        ..Default::default()
+                    },
+                );
+            }
+
+            for block in elements.iter_mut().filter(|e| is_block(e)) {
+                for group in &edited {
+                    let entry = group.to_xml();
+                    match block.children.iter_mut().find(|c| c.name == entry.name) {
+                        Some(existing) => merge_option_entry(existing, entry),
+                        None => block.children.push(entry),
+                    }
+                }
+            }
+        }
+        elements
+    }
+}
+
+/// Overlay an edited group onto the user's saved entry: values the group sets replace
+/// theirs, anything else they saved is kept.
+fn merge_option_entry(existing: &mut XmlElement, entry: XmlElement) {
+    for (k, v) in entry.attrs {
+        match existing.attrs.iter_mut().find(|(ek, _)| *ek == k) {
+            Some(slot) => slot.1 = v,
+            None => existing.attrs.push((k, v)),
+        }
+    }
+    for child in entry.children {
+        match existing.children.iter_mut().find(|c| c.name == child.name) {
+            Some(slot) => *slot = child,
+            None => existing.children.push(child),
+        }
+    }
 }
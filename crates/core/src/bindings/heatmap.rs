@@ -0,0 +1,355 @@
+//! Keyboard heatmap: which keys are taken in one category's context, drawn as an SVG.
+//!
+//! An action counts when its map's category group overlaps the chosen category's group
+//! (the generator's and the conflict report's notion of sharing a bind). Every modifier
+//! combination in use gets its own keyboard ("layer"), keys are shaded by how many
+//! actions use them, and custom/generated binds are outlined differently.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write as _,
+    sync::Arc,
+};
+
+use crate::bindings::{
+    action_bindings::ActionBindings,
+    bind::{BindMain, BindSource, DeviceKind},
+    bind_tokens::{KeyTokenExt, modifier_tokens},
+    cheat_sheet::html_escape,
+    constants::{DEFAULT_CATEGORY, category_group},
+};
+
+/// Size of a 1u key in SVG pixels.
+const UNIT: f32 = 40.0;
+/// Width of the drawn keyboard, in key units (main block + navigation + numpad).
+const BOARD_WIDTH: f32 = 23.0;
+/// Height of one layer, in key units (keys + caption).
+const LAYER_HEIGHT: f32 = 7.5;
+
+/// One action on a key.
+#[derive(Debug, Clone)]
+pub struct KeyUse {
+    pub action_id: Arc<str>,
+    /// Translated label (`ActionBinding::get_label`).
+    pub label: String,
+    pub source: BindSource,
+}
+
+/// Keys used with one modifier combination.
+#[derive(Debug, Clone, Default)]
+pub struct HeatmapLayer {
+    /// Held modifiers in game order ("lctrl", "lalt", ...); empty for plain keys.
+    pub modifiers: Vec<&'static str>,
+    /// Key token → actions using it.
+    pub keys: BTreeMap<&'static str, Vec<KeyUse>>,
+}
+
+impl HeatmapLayer {
+    /// Modifiers joined with "+", or "none".
+    pub fn name(&self) -> String {
+        if self.modifiers.is_empty() {
+            "none".into()
+        } else {
+            self.modifiers.join("+")
+        }
+    }
+}
+
+/// Keyboard usage seen from one category.
+#[derive(Debug, Clone, Default)]
+pub struct KeyboardHeatmap {
+    pub category: String,
+    /// Categories whose actions were counted, sorted.
+    pub group: Vec<String>,
+    /// The unmodified layer first (always present), then by modifier count and name.
+    pub layers: Vec<HeatmapLayer>,
+}
+
+impl KeyboardHeatmap {
+    /// Keyboard binds in effect (`ActionBinding::active_binds`) of every action sharing
+    /// `category`'s group; `group_map` has the shape of `CATEGORY_GROUPS`.
+    pub fn build(
+        bindings: &ActionBindings,
+        translations: &HashMap<String, String>,
+        category: &str,
+        group_map: &HashMap<String, HashSet<String>>,
+    ) -> Self {
+        let wanted = category_group(group_map, category);
+
+        let mut group = HashSet::new();
+        let mut layers: BTreeMap<(usize, Vec<&'static str>), HeatmapLayer> = BTreeMap::new();
+        layers.insert((0, Vec::new()), HeatmapLayer::default());
+
+        for map in bindings.action_maps.values() {
+            let cat = map.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+            if category_group(group_map, cat).is_disjoint(&wanted) {
+                continue;
+            }
+            group.insert(cat.to_string());
+            for action in map.actions.values() {
+                for (device, bind, source) in action.active_binds() {
+                    if device != DeviceKind::Keyboard {
+                        continue;
+                    }
+                    let Some(BindMain::Key(key)) = bind.main.as_ref() else {
+                        continue;
+                    };
+                    let modifiers = modifier_tokens(&bind.modifiers);
+                    layers
+                        .entry((modifiers.len(), modifiers.clone()))
+                        .or_insert_with(|| HeatmapLayer {
+                            modifiers,
+                            ..Default::default()
+                        })
+                        .keys
+                        .entry(key.to_token())
+                        .or_default()
+                        .push(KeyUse {
+                            action_id: action.action_id.clone(),
+                            label: action.get_label(translations),
+                            source,
+                        });
+                }
+            }
+        }
+
+        let mut group: Vec<String> = group.into_iter().collect();
+        group.sort();
+        Self {
+            category: category.to_string(),
+            group,
+            layers: layers.into_values().collect(),
+        }
+    }
+
+    /// Most actions on any one key, over all layers.
+    pub fn max_count(&self) -> usize {
+        self.layers
+            .iter()
+            .flat_map(|l| l.keys.values())
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// All layers stacked top to bottom in one SVG, with a legend. Hovering a key lists
+    /// its actions.
+    pub fn to_svg(&self) -> String {
+        let layout = keyboard_layout();
+        let width = BOARD_WIDTH * UNIT + 2.0 * UNIT;
+        let height = (self.layers.len() as f32 * LAYER_HEIGHT + 2.0) * UNIT;
+
+        let mut out = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">
+<style>
+.key {{ stroke: #9e9e9e; stroke-width: 1; }}
+.custom {{ stroke: #1565c0; stroke-width: 3; }}
+.generated {{ stroke: #2e7d32; stroke-width: 3; stroke-dasharray: 5 3; }}
+.held {{ fill: #616161; }}
+.cap {{ font-size: 10px; fill: #212121; }}
+.count {{ font-size: 12px; font-weight: bold; fill: #212121; text-anchor: end; }}
+</style>
+<rect width="100%" height="100%" fill="#ffffff"/>
+<text x="{UNIT}" y="{title_y}" font-size="18">{title}</text>
+"##,
+            title_y = UNIT * 0.7,
+            title = html_escape(&format!(
+                "Keyboard use for {} ({})",
+                self.category,
+                self.group.join(", ")
+            )),
+        );
+        self.write_legend(&mut out);
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            let top = (1.5 + i as f32 * LAYER_HEIGHT) * UNIT;
+            let _ = writeln!(
+                out,
+                r#"<g id="layer-{name}" transform="translate({UNIT} {top})">
+<text x="0" y="{caption_y}" font-size="15">Modifiers: {name} ({count} keys used)</text>"#,
+                name = html_escape(&layer.name()),
+                caption_y = UNIT * 0.6,
+                count = layer.keys.len(),
+            );
+            for cap in &layout {
+                self.write_key(&mut out, layer, cap);
+            }
+            out.push_str("</g>\n");
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    fn write_key(&self, out: &mut String, layer: &HeatmapLayer, cap: &KeyCap) {
+        let uses = layer.keys.get(cap.token).map(Vec::as_slice).unwrap_or(&[]);
+        let held = layer.modifiers.contains(&cap.token);
+
+        let mut class = String::from("key");
+        if uses.iter().any(|u| u.source == BindSource::Custom) {
+            class.push_str(" custom");
+        } else if uses.iter().any(|u| u.source == BindSource::Generated) {
+            class.push_str(" generated");
+        }
+        if held {
+            class.push_str(" held");
+        }
+
+        let (x, y) = (cap.x * UNIT + 2.0, (cap.y + 1.0) * UNIT + 2.0);
+        let (w, h) = (cap.w * UNIT - 4.0, cap.h * UNIT - 4.0);
+        let _ = write!(
+            out,
+            r#"<g><rect class="{class}" x="{x}" y="{y}" width="{w}" height="{h}" rx="4" fill="{fill}"/>"#,
+            fill = heat_color(uses.len()),
+        );
+        let _ = write!(
+            out,
+            r#"<text class="cap" x="{tx}" y="{ty}">{label}</text>"#,
+            tx = x + 4.0,
+            ty = y + 12.0,
+            label = html_escape(cap.label),
+        );
+        if !uses.is_empty() {
+            let _ = write!(
+                out,
+                r#"<text class="count" x="{tx}" y="{ty}">{n}</text>"#,
+                tx = x + w - 4.0,
+                ty = y + h - 4.0,
+                n = uses.len(),
+            );
+            let tip: Vec<String> = uses
+                .iter()
+                .map(|u| format!("{} ({}, {})", u.label, u.action_id, u.source))
+                .collect();
+            let _ = write!(out, "<title>{}</title>", html_escape(&tip.join("\n")));
+        }
+        out.push_str("</g>\n");
+    }
+
+    fn write_legend(&self, out: &mut String) {
+        let y = UNIT * 0.45;
+        let mut x = BOARD_WIDTH * UNIT - 11.0 * UNIT;
+        for (n, text) in [(0, "free"), (1, "1"), (2, "2"), (3, "3"), (4, "4+")] {
+            let _ = writeln!(
+                out,
+                r#"<rect class="key" x="{x}" y="{y}" width="18" height="18" fill="{}"/><text class="cap" x="{}" y="{}">{text}</text>"#,
+                heat_color(n),
+                x + 22.0,
+                y + 13.0,
+            );
+            x += UNIT * 1.2;
+        }
+        for (class, text) in [("custom", "custom"), ("generated", "generated")] {
+            let _ = writeln!(
+                out,
+                r#"<rect class="key {class}" x="{x}" y="{y}" width="18" height="18" fill="{}"/><text class="cap" x="{}" y="{}">{text}</text>"#,
+                heat_color(1),
+                x + 22.0,
+                y + 13.0,
+            );
+            x += UNIT * 2.0;
+        }
+    }
+}
+
+/// Fill for a key used by `n` actions (grey when free).
+fn heat_color(n: usize) -> &'static str {
+    match n {
+        0 => "#f5f5f5",
+        1 => "#ffe0b2",
+        2 => "#ffb74d",
+        3 => "#fb8c00",
+        _ => "#e65100",
+    }
+}
+
+/// One key on the drawn keyboard; positions and sizes in key units.
+struct KeyCap {
+    token: &'static str,
+    label: &'static str,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+/// (token, label, width) of one key; an empty token is a gap.
+type LayoutKey = (&'static str, &'static str, f32);
+
+/// Rows of a full-size ANSI keyboard: (x, y, keys).
+#[rustfmt::skip]
+const ROWS: &[(f32, f32, &[LayoutKey])] = &[
+    // Main block
+    (0.0, 0.0, &[
+        ("escape", "Esc", 1.0), ("", "", 1.0),
+        ("f1", "F1", 1.0), ("f2", "F2", 1.0), ("f3", "F3", 1.0), ("f4", "F4", 1.0), ("", "", 0.5),
+        ("f5", "F5", 1.0), ("f6", "F6", 1.0), ("f7", "F7", 1.0), ("f8", "F8", 1.0), ("", "", 0.5),
+        ("f9", "F9", 1.0), ("f10", "F10", 1.0), ("f11", "F11", 1.0), ("f12", "F12", 1.0),
+    ]),
+    (0.0, 1.25, &[
+        ("grave", "`", 1.0), ("1", "1", 1.0), ("2", "2", 1.0), ("3", "3", 1.0), ("4", "4", 1.0),
+        ("5", "5", 1.0), ("6", "6", 1.0), ("7", "7", 1.0), ("8", "8", 1.0), ("9", "9", 1.0),
+        ("0", "0", 1.0), ("minus", "-", 1.0), ("equals", "=", 1.0), ("backspace", "Backspace", 2.0),
+    ]),
+    (0.0, 2.25, &[
+        ("tab", "Tab", 1.5), ("q", "Q", 1.0), ("w", "W", 1.0), ("e", "E", 1.0), ("r", "R", 1.0),
+        ("t", "T", 1.0), ("y", "Y", 1.0), ("u", "U", 1.0), ("i", "I", 1.0), ("o", "O", 1.0),
+        ("p", "P", 1.0), ("lbracket", "[", 1.0), ("rbracket", "]", 1.0), ("backslash", "\\", 1.5),
+    ]),
+    (0.0, 3.25, &[
+        ("capslock", "Caps", 1.75), ("a", "A", 1.0), ("s", "S", 1.0), ("d", "D", 1.0),
+        ("f", "F", 1.0), ("g", "G", 1.0), ("h", "H", 1.0), ("j", "J", 1.0), ("k", "K", 1.0),
+        ("l", "L", 1.0), ("semicolon", ";", 1.0), ("apostrophe", "'", 1.0), ("enter", "Enter", 2.25),
+    ]),
+    (0.0, 4.25, &[
+        ("lshift", "LShift", 2.25), ("z", "Z", 1.0), ("x", "X", 1.0), ("c", "C", 1.0),
+        ("v", "V", 1.0), ("b", "B", 1.0), ("n", "N", 1.0), ("m", "M", 1.0), ("comma", ",", 1.0),
+        ("period", ".", 1.0), ("slash", "/", 1.0), ("rshift", "RShift", 2.75),
+    ]),
+    (0.0, 5.25, &[
+        ("lctrl", "LCtrl", 1.25), ("lwin", "LWin", 1.25), ("lalt", "LAlt", 1.25),
+        ("space", "Space", 6.25), ("ralt", "RAlt", 1.25), ("rwin", "RWin", 1.25),
+        ("menu", "Menu", 1.25), ("rctrl", "RCtrl", 1.25),
+    ]),
+    // Navigation
+    (15.25, 0.0, &[("print", "PrtSc", 1.0), ("", "", 1.0), ("pause", "Pause", 1.0)]),
+    (15.25, 1.25, &[("insert", "Ins", 1.0), ("home", "Home", 1.0), ("pgup", "PgUp", 1.0)]),
+    (15.25, 2.25, &[("delete", "Del", 1.0), ("end", "End", 1.0), ("pgdn", "PgDn", 1.0)]),
+    (16.25, 4.25, &[("up", "↑", 1.0)]),
+    (15.25, 5.25, &[("left", "←", 1.0), ("down", "↓", 1.0), ("right", "→", 1.0)]),
+    // Numpad
+    (18.75, 1.25, &[
+        ("np_lock", "Num", 1.0), ("np_divide", "/", 1.0), ("np_multiply", "*", 1.0),
+        ("np_subtract", "-", 1.0),
+    ]),
+    (18.75, 2.25, &[("np_7", "7", 1.0), ("np_8", "8", 1.0), ("np_9", "9", 1.0), ("np_add", "+", 1.0)]),
+    (18.75, 3.25, &[("np_4", "4", 1.0), ("np_5", "5", 1.0), ("np_6", "6", 1.0)]),
+    (18.75, 4.25, &[("np_1", "1", 1.0), ("np_2", "2", 1.0), ("np_3", "3", 1.0), ("np_enter", "Ent", 1.0)]),
+    (18.75, 5.25, &[("np_0", "0", 2.0), ("np_period", ".", 1.0)]),
+];
+
+fn keyboard_layout() -> Vec<KeyCap> {
+    let mut caps = Vec::new();
+    for &(x0, y, keys) in ROWS {
+        let mut x = x0;
+        for &(token, label, w) in keys {
+            if !token.is_empty() {
+                // Numpad + and Enter span two rows
+                let h = if matches!(token, "np_add" | "np_enter") {
+                    2.0
+                } else {
+                    1.0
+                };
+                caps.push(KeyCap {
+                    token,
+                    label,
+                    x,
+                    y,
+                    w,
+                    h,
+                });
+            }
+            x += w;
+        }
+    }
+    caps
+}
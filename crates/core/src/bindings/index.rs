@@ -0,0 +1,356 @@
+//! Reverse lookup over a loaded `ActionBindings`: who uses a bind, and which actions
+//! belong to a category group, action map, bind origin or activation mode.
+//!
+//! The index is built once per bindings snapshot and keeps that snapshot alive, so
+//! queries hand out references instead of cloning actions. Only binds in effect count
+//! (`ActionBinding::active_binds`: custom over default per device, no explicit unbinds).
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::bindings::{
+    action_binding::ActionBinding,
+    action_bindings::ActionBindings,
+    action_group::ACTION_GROUP_PREFIX,
+    bind::{Bind, BindSource, DeviceKind},
+    constants::{DEFAULT_CATEGORY, category_group},
+};
+
+/// Short names for the usual play contexts → UI category.
+const CONTEXT_ALIASES: &[(&str, &str)] = &[
+    ("flight", "@ui_CCSpaceFlight"),
+    ("seat", "@ui_CCSeatGeneral"),
+    ("vehicle", "@ui_CCVehicle"),
+    ("turret", "@ui_CCTurrets"),
+    ("fps", "@ui_CCFPS"),
+    ("onfoot", "@ui_CCFPS"),
+    ("eva", "@ui_CCEVA"),
+    ("map", "@ui_Map"),
+    ("camera", "@ui_CCCamera"),
+    ("spectator", "@ui_CGEASpectator"),
+    ("ui", "@ui_CGUIGeneral"),
+];
+
+/// UI category for a context name: an alias ("flight", "fps", ...), a category as
+/// written ("@ui_CCSpaceFlight"), or one without its "@ui_" prefix ("CCSpaceFlight").
+pub fn resolve_context(name: &str) -> String {
+    if let Some((_, category)) = CONTEXT_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+    {
+        return category.to_string();
+    }
+    if name.starts_with('@') {
+        name.to_string()
+    } else {
+        format!("@ui_{name}")
+    }
+}
+
+/// Position of an action in `ActionBindings::action_maps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ActionRef {
+    map: usize,
+    action: usize,
+}
+
+/// One bind in effect, as stored by the index.
+#[derive(Debug, Clone)]
+struct Use {
+    at: ActionRef,
+    device: DeviceKind,
+    bind: Bind,
+    source: BindSource,
+    activation_mode: Option<String>,
+}
+
+/// One action reacting to a bind.
+#[derive(Debug, Clone, Copy)]
+pub struct BindUse<'a> {
+    pub action: &'a ActionBinding,
+    pub bind: &'a Bind,
+    pub device: DeviceKind,
+    pub source: BindSource,
+    /// Activation mode of the bind, else of the action.
+    pub activation_mode: Option<&'a str>,
+    /// UI category of the action's map (`DEFAULT_CATEGORY` if it has none).
+    pub category: &'a str,
+}
+
+/// Lookup tables over one bindings snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct BindingsIndex {
+    bindings: Arc<ActionBindings>,
+    by_id: HashMap<Arc<str>, ActionRef>,
+    /// Every bind in effect, in profile order; the maps below point into it.
+    uses: Vec<Use>,
+    by_bind: HashMap<Bind, Vec<usize>>,
+    by_source: HashMap<BindSource, Vec<usize>>,
+    by_mode: HashMap<String, Vec<usize>>,
+    /// UI category → indices into `action_maps`.
+    by_category: HashMap<String, Vec<usize>>,
+}
+
+impl BindingsIndex {
+    pub fn new(bindings: Arc<ActionBindings>) -> Self {
+        let mut index = BindingsIndex::default();
+
+        for (m, map) in bindings.action_maps.values().enumerate() {
+            let category = map.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+            index
+                .by_category
+                .entry(category.to_string())
+                .or_default()
+                .push(m);
+
+            for (a, action) in map.actions.values().enumerate() {
+                let at = ActionRef { map: m, action: a };
+                index.by_id.insert(action.action_id.clone(), at);
+
+                for (device, bind, source) in action.active_binds() {
+                    let ix = index.uses.len();
+                    let activation_mode = action.mode_name(bind, &bindings.activation);
+                    index.by_bind.entry(bind.clone()).or_default().push(ix);
+                    index.by_source.entry(source).or_default().push(ix);
+                    if let Some(mode) = activation_mode.as_ref() {
+                        index.by_mode.entry(mode.clone()).or_default().push(ix);
+                    }
+                    index.uses.push(Use {
+                        at,
+                        device,
+                        bind: bind.clone(),
+                        source,
+                        activation_mode,
+                    });
+                }
+            }
+        }
+
+        index.bindings = bindings;
+        index
+    }
+
+    /// The snapshot this index was built from.
+    pub fn bindings(&self) -> &Arc<ActionBindings> {
+        &self.bindings
+    }
+
+    /// Action by id (`map.action`).
+    pub fn get(&self, id: &str) -> Option<&ActionBinding> {
+        self.by_id.get(id).map(|&at| self.action(at))
+    }
+
+    /// Like `ActionBindings::resolve_actions`: one action, or every member of
+    /// `group:<name>`.
+    pub fn resolve(&self, id: &str) -> Vec<&ActionBinding> {
+        match id.strip_prefix(ACTION_GROUP_PREFIX) {
+            Some(group) => self
+                .bindings
+                .action_groups
+                .get(group)
+                .into_iter()
+                .flat_map(|g| &g.members)
+                .filter_map(|m| self.get(m))
+                .collect(),
+            None => self.get(id).into_iter().collect(),
+        }
+    }
+
+    /// `resolve`, keeping the first action per bind a press sends (`executable_bind`).
+    /// Group members often share one, and sending it once per member would repeat a tap
+    /// or undo a toggle. Actions with nothing to send are kept so callers can report them.
+    pub fn resolve_presses(&self, id: &str) -> Vec<&ActionBinding> {
+        let mut sent = HashSet::new();
+        self.resolve(id)
+            .into_iter()
+            .filter(|a| match a.executable_bind() {
+                Some(bind) => sent.insert(bind),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Actions that react to `bind` (modifiers and main input must match; activation
+    /// mode and device instance are ignored).
+    pub fn by_bind(&self, bind: &Bind) -> Vec<BindUse<'_>> {
+        self.collect(self.by_bind.get(bind))
+    }
+
+    /// `by_bind` for a bind string as the profile writes it ("lalt+f", "mouse4",
+    /// "js1_button3").
+    pub fn by_bind_str(&self, input: &str) -> Result<Vec<BindUse<'_>>, String> {
+        let bind = Bind::from_string(input, None).map_err(|e| format!("bind '{input}': {e}"))?;
+        if bind.is_unbound {
+            return Err("empty bind".into());
+        }
+        Ok(self.by_bind(&bind))
+    }
+
+    /// Binds in effect that come from `source`.
+    pub fn by_source(&self, source: BindSource) -> Vec<BindUse<'_>> {
+        self.collect(self.by_source.get(&source))
+    }
+
+    /// Binds in effect running with the activation mode called `name`.
+    pub fn by_activation_mode(&self, name: &str) -> Vec<BindUse<'_>> {
+        self.collect(self.by_mode.get(name))
+    }
+
+    /// Actions of the action map called `name`, in profile order.
+    pub fn in_action_map(&self, name: &str) -> Vec<&ActionBinding> {
+        self.bindings
+            .action_maps
+            .get(name)
+            .map(|m| m.actions.values().collect())
+            .unwrap_or_default()
+    }
+
+    /// Actions whose map is in `category` itself.
+    pub fn in_category(&self, category: &str) -> Vec<&ActionBinding> {
+        self.maps_in([category])
+    }
+
+    /// Actions sharing binds with `category`: their map's category group overlaps
+    /// `category`'s (`group_map` has the shape of `CATEGORY_GROUPS`).
+    pub fn in_group(
+        &self,
+        category: &str,
+        group_map: &HashMap<String, HashSet<String>>,
+    ) -> Vec<&ActionBinding> {
+        self.maps_in(self.group_categories(category, group_map))
+    }
+
+    /// Categories of this profile that share a group with `category` (itself included
+    /// if present).
+    pub fn group_categories<'a>(
+        &'a self,
+        category: &str,
+        group_map: &HashMap<String, HashSet<String>>,
+    ) -> HashSet<&'a str> {
+        let wanted = category_group(group_map, category);
+        self.by_category
+            .keys()
+            .filter(|cat| !category_group(group_map, cat).is_disjoint(&wanted))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Every known UI category, sorted.
+    pub fn categories(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = self.by_category.keys().map(String::as_str).collect();
+        out.sort();
+        out
+    }
+
+    fn maps_in<'a>(&self, categories: impl IntoIterator<Item = &'a str>) -> Vec<&ActionBinding> {
+        let mut maps: Vec<usize> = categories
+            .into_iter()
+            .filter_map(|c| self.by_category.get(c))
+            .flatten()
+            .copied()
+            .collect();
+        maps.sort_unstable();
+        maps.into_iter()
+            .filter_map(|m| self.bindings.action_maps.get_index(m))
+            .flat_map(|(_, map)| map.actions.values())
+            .collect()
+    }
+
+    fn collect(&self, ixs: Option<&Vec<usize>>) -> Vec<BindUse<'_>> {
+        ixs.into_iter()
+            .flatten()
+            .map(|&ix| {
+                let u = &self.uses[ix];
+                let map = &self.bindings.action_maps[u.at.map];
+                BindUse {
+                    action: self.action(u.at),
+                    bind: &u.bind,
+                    device: u.device,
+                    source: u.source,
+                    activation_mode: u.activation_mode.as_deref(),
+                    category: map.ui_category.as_deref().unwrap_or(DEFAULT_CATEGORY),
+                }
+            })
+            .collect()
+    }
+
+    fn action(&self, at: ActionRef) -> &ActionBinding {
+        &self.bindings.action_maps[at.map].actions[at.action]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_log::{CoreLog, NoopLog};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PROFILE: &str = r#"<profile version="1">
+  <actiongroup action="v_attack">
+    <action name="v_attack_all" />
+    <action name="v_attack_group1" />
+    <action name="v_attack_group2" />
+  </actiongroup>
+  <ActivationModes>
+    <ActivationMode name="press" onPress="1" onHold="0" onRelease="0" multiTap="1" multiTapBlock="1" pressTriggerThreshold="-1" releaseTriggerThreshold="-1" releaseTriggerDelay="0" retriggerable="0" />
+  </ActivationModes>
+  <actionmap name="spaceship_weapons" version="1">
+    <action name="v_attack_all" activationMode="press" keyboard="f" />
+    <action name="v_attack_group1" activationMode="press" keyboard="f" />
+    <action name="v_attack_group2" activationMode="press" keyboard="g" />
+  </actionmap>
+</profile>"#;
+
+    fn index() -> BindingsIndex {
+        // One file per call: tests run in parallel
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let n = CALLS.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("sc-core-index-{}-{n}.xml", std::process::id()));
+        std::fs::write(&path, PROFILE).unwrap();
+        let logger: Arc<dyn CoreLog> = Arc::new(NoopLog);
+        let mut bindings = ActionBindings::default();
+        let loaded =
+            bindings.load_default_profile(&path, &HashSet::new(), &HashMap::new(), &logger);
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.unwrap().is_clean());
+        bindings.into_index()
+    }
+
+    fn ids(actions: Vec<&ActionBinding>) -> Vec<&str> {
+        actions.into_iter().map(|a| &*a.action_id).collect()
+    }
+
+    #[test]
+    fn resolve_keeps_every_group_member() {
+        assert_eq!(
+            ids(index().resolve("group:v_attack")),
+            [
+                "spaceship_weapons.v_attack_all",
+                "spaceship_weapons.v_attack_group1",
+                "spaceship_weapons.v_attack_group2",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_presses_sends_a_shared_bind_once() {
+        assert_eq!(
+            ids(index().resolve_presses("group:v_attack")),
+            [
+                "spaceship_weapons.v_attack_all",
+                "spaceship_weapons.v_attack_group2",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_presses_keeps_a_single_action() {
+        assert_eq!(
+            ids(index().resolve_presses("spaceship_weapons.v_attack_group1")),
+            ["spaceship_weapons.v_attack_group1"]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! What `load_default_profile` and `apply_custom_profile` dropped or kept verbatim:
+//! one issue per element, with its kind, action and position in the XML, so front
+//! ends can say "12 custom rebinds reference actions that no longer exist" and list
+//! them instead of leaving a trail of warnings.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use roxmltree::Document;
+use serde::{Deserialize, Serialize};
+
+use crate::core_log::CoreLog;
+
+/// What went wrong with one element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadIssueKind {
+    /// `<optiontree>` that failed to parse (dropped).
+    BadOptionTree,
+    /// `<actionmap>` that failed to parse (dropped).
+    BadActionMap,
+    /// `<action>` that failed to parse (dropped).
+    BadAction,
+    /// Bind that failed to parse (dropped from defaults, kept verbatim from custom).
+    BadBind,
+    /// `<actiongroup>` that failed to parse (dropped).
+    BadActionGroup,
+    /// Action group member that isn't a loaded action.
+    UnknownGroupMember,
+    /// Rebind input without a known device prefix (kept verbatim).
+    UnknownDevice,
+    /// Custom rebinds for an action the defaults don't have (kept verbatim).
+    UnknownAction,
+    /// Rebind naming an activation mode the defaults don't define (kept verbatim).
+    UnknownActivationMode,
+}
+
+impl LoadIssueKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoadIssueKind::BadOptionTree => "bad_option_tree",
+            LoadIssueKind::BadActionMap => "bad_action_map",
+            LoadIssueKind::BadAction => "bad_action",
+            LoadIssueKind::BadBind => "bad_bind",
+            LoadIssueKind::BadActionGroup => "bad_action_group",
+            LoadIssueKind::UnknownGroupMember => "unknown_group_member",
+            LoadIssueKind::UnknownDevice => "unknown_device",
+            LoadIssueKind::UnknownAction => "unknown_action",
+            LoadIssueKind::UnknownActivationMode => "unknown_activation_mode",
+        }
+    }
+
+    /// What follows the count in a summary line, singular or plural.
+    fn describe(&self, count: usize) -> &'static str {
+        let one = count == 1;
+        match self {
+            LoadIssueKind::BadOptionTree if one => "option tree could not be parsed",
+            LoadIssueKind::BadOptionTree => "option trees could not be parsed",
+            LoadIssueKind::BadActionMap if one => "action map could not be parsed",
+            LoadIssueKind::BadActionMap => "action maps could not be parsed",
+            LoadIssueKind::BadAction if one => "action could not be parsed",
+            LoadIssueKind::BadAction => "actions could not be parsed",
+            LoadIssueKind::BadBind if one => "bind could not be parsed",
+            LoadIssueKind::BadBind => "binds could not be parsed",
+            LoadIssueKind::BadActionGroup if one => "action group could not be parsed",
+            LoadIssueKind::BadActionGroup => "action groups could not be parsed",
+            LoadIssueKind::UnknownGroupMember if one => {
+                "action group member references an action that doesn't exist"
+            }
+            LoadIssueKind::UnknownGroupMember => {
+                "action group members reference actions that don't exist"
+            }
+            LoadIssueKind::UnknownDevice if one => "rebind has no known device prefix",
+            LoadIssueKind::UnknownDevice => "rebinds have no known device prefix",
+            LoadIssueKind::UnknownAction if one => {
+                "custom rebind references an action that no longer exists"
+            }
+            LoadIssueKind::UnknownAction => "custom rebinds reference actions that no longer exist",
+            LoadIssueKind::UnknownActivationMode if one => "rebind uses an unknown activation mode",
+            LoadIssueKind::UnknownActivationMode => "rebinds use unknown activation modes",
+        }
+    }
+}
+
+/// One dropped or kept-verbatim element.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadIssue {
+    pub kind: LoadIssueKind,
+    /// `map.action` the issue belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    pub detail: String,
+    /// 1-based position of the element in the XML.
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for LoadIssue {
+    /// "line:col map.action: detail"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)?;
+        if let Some(id) = self.action_id.as_deref() {
+            write!(f, " {id}")?;
+        }
+        write!(f, ": {}", self.detail)
+    }
+}
+
+/// Outcome of loading one profile file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadReport {
+    pub path: PathBuf,
+    /// Action maps loaded (defaults) or with rebinds applied (custom).
+    pub action_maps: usize,
+    /// Actions loaded (defaults) or with rebinds applied (custom).
+    pub actions: usize,
+    /// Activation modes known after loading.
+    pub activation_modes: usize,
+    /// `<rebind>`s applied (custom only).
+    pub rebinds: usize,
+    /// In document order.
+    pub issues: Vec<LoadIssue>,
+}
+
+impl LoadReport {
+    pub(crate) fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Record an issue for the element starting at byte `pos` of `doc`.
+    pub(crate) fn push(
+        &mut self,
+        doc: &Document,
+        pos: usize,
+        kind: LoadIssueKind,
+        action_id: Option<String>,
+        detail: String,
+    ) {
+        let at = doc.text_pos_at(pos);
+        self.issues.push(LoadIssue {
+            kind,
+            action_id,
+            detail,
+            line: at.row,
+            column: at.col,
+        });
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn count(&self, kind: LoadIssueKind) -> usize {
+        self.of_kind(kind).count()
+    }
+
+    pub fn of_kind(&self, kind: LoadIssueKind) -> impl Iterator<Item = &LoadIssue> {
+        self.issues.iter().filter(move |i| i.kind == kind)
+    }
+
+    /// Kinds present, in `LoadIssueKind` order.
+    pub fn kinds(&self) -> Vec<LoadIssueKind> {
+        let mut kinds: Vec<LoadIssueKind> = self.issues.iter().map(|i| i.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        kinds
+    }
+
+    /// One line per kind present ("12 custom rebinds reference actions that no longer
+    /// exist").
+    pub fn summary(&self) -> Vec<String> {
+        self.kinds()
+            .into_iter()
+            .map(|kind| {
+                let n = self.count(kind);
+                format!("{n} {}", kind.describe(n))
+            })
+            .collect()
+    }
+
+    /// Each summary line as a warning, followed by its issues ("path:line:col ...").
+    pub fn log(&self, logger: &Arc<dyn CoreLog>) {
+        let file = self.path.display();
+        for (kind, line) in self.kinds().into_iter().zip(self.summary()) {
+            logger.warn(&format!("{file}: {line}"));
+            for issue in self.of_kind(kind) {
+                logger.warn(&format!("  {file}:{issue}"));
+            }
+        }
+    }
+}
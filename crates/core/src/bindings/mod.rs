@@ -1,14 +1,28 @@
 // Public API surface of the bindings module.
 pub mod action_binding;
 pub mod action_bindings;
+pub mod action_group;
 pub mod action_map;
 pub mod activation_mode;
 pub mod bind;
+pub mod bind_cost;
+pub mod bindgen_config;
 pub mod binds;
 pub mod binds_generator;
+pub mod changelog;
+pub mod cheat_sheet;
+pub mod conflicts;
 pub mod constants;
+pub mod heatmap;
+pub mod index;
+pub mod load_report;
+pub mod options;
+pub mod passthrough;
+pub mod profile_diff;
+pub mod search;
 
 // Internal helpers (available within the crate)
+pub(crate) mod assignment;
 pub(crate) mod bind_tokens;
 pub(crate) mod generate_mappings_xml;
 pub(crate) mod str_intern;
@@ -0,0 +1,336 @@
+//! Axis tuning model: `<optiontree>` / `<optiongroup>` (core-friendly).
+//!
+//! - One `OptionTree` per device type (`keyboard` covers the mouse too).
+//! - `OptionGroup`s nest; a value not set on a group is inherited from the nearest
+//!   ancestor that sets it (`OptionTree::effective`); `<nonlinearity_curve reset="1"/>`
+//!   stops the curve from being inherited.
+//! - Actions reference a group by name via `optionGroup=` (`ActionBinding::option_group`).
+
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::bindings::{bind::DeviceKind, passthrough::XmlElement, str_intern::intern};
+
+/// `UIShow*` tri-state.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UiShow {
+    /// `"0"`
+    Hidden,
+    /// `"1"`
+    Shown,
+    /// `"-1"`: not shown on this level, children decide.
+    Children,
+}
+
+impl UiShow {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "0" => Some(UiShow::Hidden),
+            "1" => Some(UiShow::Shown),
+            "-1" => Some(UiShow::Children),
+            _ => None,
+        }
+    }
+}
+
+/// One `<point in="" out=""/>` of a `<nonlinearity_curve>`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CurvePoint {
+    pub input: f32,
+    pub output: f32,
+}
+
+/// A single `<optiongroup>` and its subgroups.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OptionGroup {
+    pub name: Arc<str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_label: Option<Arc<str>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invert: Option<bool>,
+    /// Console variable the game mirrors `invert` into (e.g. `cl_invertMouse`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invert_cvar: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exponent: Option<f32>,
+    /// `<nonlinearity_curve>` points in source order (empty = no curve on this level).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub curve: Vec<CurvePoint>,
+    /// `<nonlinearity_curve reset="1"/>`: drop the curve inherited from ancestors.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub curve_reset: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_invert: Option<UiShow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_sensitivity: Option<UiShow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_curve: Option<UiShow>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OptionGroup>,
+
+    /// Handed out by `ActionBindings::option_group_mut`; `generate_mapping_xml` writes
+    /// edited groups into the profile's `<options>`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub edited: bool,
+}
+
+/// A `<optiontree>` root for one device type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OptionTree {
+    pub device: DeviceKind,
+    pub name: Arc<str>,
+    /// Number of device instances the tree applies to (`instances="8"` on joysticks).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instances: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity_min: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity_max: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_invert: Option<UiShow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_sensitivity: Option<UiShow>,
+    pub groups: Vec<OptionGroup>,
+}
+
+/// Values that apply to a group after inheritance from its ancestors.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectiveOptions<'a> {
+    pub invert: bool,
+    pub sensitivity: Option<f32>,
+    pub exponent: Option<f32>,
+    pub curve: &'a [CurvePoint],
+}
+
+#[derive(Debug)]
+pub enum OptionParseError {
+    MissingName,
+    UnknownDeviceType(String),
+}
+
+#[inline]
+fn f32_attr(node: Node, key: &str) -> Option<f32> {
+    node.attribute(key)
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|v| v.is_finite())
+}
+
+#[inline]
+fn show_attr(node: Node, key: &str) -> Option<UiShow> {
+    node.attribute(key).and_then(UiShow::parse)
+}
+
+impl OptionGroup {
+    /// Parse an `<optiongroup>` node and its subgroups.
+    pub fn from_node(node: Node) -> Result<Self, OptionParseError> {
+        let name = node
+            .attribute("name")
+            .ok_or(OptionParseError::MissingName)?;
+
+        let curves = || {
+            node.children()
+                .filter(|n| n.has_tag_name("nonlinearity_curve"))
+        };
+        let curve = curves()
+            .flat_map(|n| n.children().filter(|p| p.has_tag_name("point")))
+            .filter_map(|p| {
+                Some(CurvePoint {
+                    input: f32_attr(p, "in")?,
+                    output: f32_attr(p, "out")?,
+                })
+            })
+            .collect();
+
+        let children = node
+            .children()
+            .filter(|n| n.has_tag_name("optiongroup"))
+            .map(OptionGroup::from_node)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(OptionGroup {
+            name: intern(name),
+            ui_label: node
+                .attribute("UILabel")
+                .filter(|s| !s.trim().is_empty())
+                .map(intern),
+            invert: node.attribute("invert").map(|v| v.trim() == "1"),
+            invert_cvar: node.attribute("invert_cvar").map(str::to_string),
+            sensitivity: f32_attr(node, "sensitivity"),
+            exponent: f32_attr(node, "exponent"),
+            curve,
+            curve_reset: curves().any(|n| n.attribute("reset").is_some_and(|v| v.trim() == "1")),
+            show_invert: show_attr(node, "UIShowInvert"),
+            show_sensitivity: show_attr(node, "UIShowSensitivity"),
+            show_curve: show_attr(node, "UIShowCurve"),
+            children,
+            edited: false,
+        })
+    }
+
+    /// Chain of groups from `self` down to the group called `name` (inclusive).
+    fn path_to<'a>(&'a self, name: &str, out: &mut Vec<&'a OptionGroup>) -> bool {
+        out.push(self);
+        if &*self.name == name || self.children.iter().any(|c| c.path_to(name, out)) {
+            return true;
+        }
+        out.pop();
+        false
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut OptionGroup> {
+        if &*self.name == name {
+            return Some(self);
+        }
+        self.children.iter_mut().find_map(|c| c.find_mut(name))
+    }
+
+    fn collect_edited<'a>(&'a self, out: &mut Vec<&'a OptionGroup>) {
+        if self.edited {
+            out.push(self);
+        }
+        for c in &self.children {
+            c.collect_edited(out);
+        }
+    }
+
+    /// This group's own values as an `<options>` child, the way the game saves them:
+    /// `<flight_move_pitch invert="1" exponent="2"><nonlinearity_curve>...`.
+    pub fn to_xml(&self) -> XmlElement {
+        let mut attrs = Vec::new();
+        if let Some(invert) = self.invert {
+            attrs.push((
+                "invert".to_string(),
+                if invert { "1" } else { "0" }.to_string(),
+            ));
+        }
+        if let Some(v) = self.sensitivity {
+            attrs.push(("sensitivity".to_string(), v.to_string()));
+        }
+        if let Some(v) = self.exponent {
+            attrs.push(("exponent".to_string(), v.to_string()));
+        }
+
+        let mut children = Vec::new();
+        if !self.curve.is_empty() || self.curve_reset {
+            children.push(XmlElement {
+                name: "nonlinearity_curve".to_string(),
+                attrs: if self.curve_reset {
+                    vec![("reset".to_string(), "1".to_string())]
+                } else {
+                    Vec::new()
+                },
+                children: self
+                    .curve
+                    .iter()
+                    .map(|p| XmlElement {
+                        name: "point".to_string(),
+                        attrs: vec![
+                            ("in".to_string(), p.input.to_string()),
+                            ("out".to_string(), p.output.to_string()),
+                        ],
+                        ..Default::default()
+                    })
+                    .collect(),
+                text: None,
+            });
+        }
+
+        XmlElement {
+            name: self.name.to_string(),
+            attrs,
+            children,
+            text: None,
+        }
+    }
+}
+
+impl OptionTree {
+    /// Parse an `<optiontree>` node.
+    pub fn from_node(node: Node) -> Result<Self, OptionParseError> {
+        let ty = node.attribute("type").unwrap_or_default();
+        let device = DeviceKind::from_name(ty)
+            .ok_or_else(|| OptionParseError::UnknownDeviceType(ty.to_string()))?;
+        let name = node
+            .attribute("name")
+            .ok_or(OptionParseError::MissingName)?;
+
+        let groups = node
+            .children()
+            .filter(|n| n.has_tag_name("optiongroup"))
+            .map(OptionGroup::from_node)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(OptionTree {
+            device,
+            name: intern(name),
+            instances: node
+                .attribute("instances")
+                .and_then(|v| v.trim().parse::<u8>().ok()),
+            sensitivity_min: f32_attr(node, "UISensitivityMin"),
+            sensitivity_max: f32_attr(node, "UISensitivityMax"),
+            show_invert: show_attr(node, "UIShowInvert"),
+            show_sensitivity: show_attr(node, "UIShowSensitivity"),
+            groups,
+        })
+    }
+
+    /// Chain of groups from the top level down to `name` (empty if not in this tree).
+    pub fn path(&self, name: &str) -> Vec<&OptionGroup> {
+        let mut out = Vec::new();
+        for g in &self.groups {
+            if g.path_to(name, &mut out) {
+                break;
+            }
+        }
+        out
+    }
+
+    /// Group called `name` anywhere in the tree.
+    pub fn find(&self, name: &str) -> Option<&OptionGroup> {
+        self.path(name).pop()
+    }
+
+    /// Group called `name` anywhere in the tree, mutably.
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut OptionGroup> {
+        self.groups.iter_mut().find_map(|g| g.find_mut(name))
+    }
+
+    /// Groups handed out for editing (`OptionGroup::edited`), in tree order.
+    pub fn edited_groups(&self) -> Vec<&OptionGroup> {
+        let mut out = Vec::new();
+        for g in &self.groups {
+            g.collect_edited(&mut out);
+        }
+        out
+    }
+
+    /// Values for `name` with unset fields inherited from the nearest ancestor.
+    pub fn effective(&self, name: &str) -> Option<EffectiveOptions<'_>> {
+        let path = self.path(name);
+        if path.is_empty() {
+            return None;
+        }
+
+        let mut eff = EffectiveOptions::default();
+        for g in path {
+            if let Some(inv) = g.invert {
+                eff.invert = inv;
+            }
+            eff.sensitivity = g.sensitivity.or(eff.sensitivity);
+            eff.exponent = g.exponent.or(eff.exponent);
+            if g.curve_reset {
+                eff.curve = &[];
+            }
+            if !g.curve.is_empty() {
+                eff.curve = &g.curve;
+            }
+        }
+        Some(eff)
+    }
+}
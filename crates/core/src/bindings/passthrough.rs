@@ -0,0 +1,149 @@
+//! Content of a user's actionmaps.xml that the bind model doesn't understand.
+//!
+//! `apply_custom_profile` collects everything it can't map onto `Binds` (device options,
+//! product GUIDs, rebinds for actions we don't load, unknown attributes, ...) into a
+//! `ProfilePassthrough`, and `generate_mapping_xml` writes it back unchanged.
+
+use indexmap::IndexMap;
+use quick_xml::Writer;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+
+/// Owned copy of an XML element (name, attributes in source order, children, text).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct XmlElement {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attrs: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<XmlElement>,
+    /// Trimmed, non-empty text content (rare in SC profiles).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl XmlElement {
+    /// Deep-copy an element node (comments and processing instructions are dropped).
+    pub fn from_node(node: Node) -> Self {
+        let text: String = node
+            .children()
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .collect::<String>()
+            .trim()
+            .to_string();
+
+        XmlElement {
+            name: node.tag_name().name().to_string(),
+            attrs: node
+                .attributes()
+                .map(|a| (a.name().to_string(), a.value().to_string()))
+                .collect(),
+            children: node
+                .children()
+                .filter(|n| n.is_element())
+                .map(XmlElement::from_node)
+                .collect(),
+            text: (!text.is_empty()).then_some(text),
+        }
+    }
+
+    #[inline]
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Write this element (and its subtree) as-is.
+    pub fn write<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), String> {
+        let mut start = BytesStart::new(self.name.as_str());
+        for (k, v) in &self.attrs {
+            start.push_attribute((k.as_str(), v.as_str()));
+        }
+
+        if self.children.is_empty() && self.text.is_none() {
+            return writer
+                .write_event(Event::Empty(start))
+                .map_err(|e| format!("<{}>: {e}", self.name));
+        }
+
+        writer
+            .write_event(Event::Start(start))
+            .map_err(|e| format!("<{}>: {e}", self.name))?;
+        if let Some(text) = self.text.as_deref() {
+            writer
+                .write_event(Event::Text(BytesText::new(text)))
+                .map_err(|e| format!("<{}> text: {e}", self.name))?;
+        }
+        for child in &self.children {
+            child.write(writer)?;
+        }
+        writer
+            .write_event(Event::End(BytesEnd::new(self.name.as_str())))
+            .map_err(|e| format!("</{}>: {e}", self.name))
+    }
+}
+
+/// Unrecognized parts of a known `<action>`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ActionPassthrough {
+    /// Attributes other than `name`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attrs: Vec<(String, String)>,
+    /// `<rebind>`s we couldn't parse and any non-`<rebind>` children.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<XmlElement>,
+}
+
+impl ActionPassthrough {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty() && self.children.is_empty()
+    }
+}
+
+/// Everything from the custom profile that isn't modeled by `ActionBindings`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ProfilePassthrough {
+    /// Unrecognized attributes on `<ActionProfiles>` (or the `<ActionMaps>` root).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub root_attrs: Vec<(String, String)>,
+    /// Top-level elements other than `<actionmap>` and `<modifiers>` (`<options>`,
+    /// `<deviceoptions>`, ...), in source order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<XmlElement>,
+    /// actionmap name → unrecognized `<actionmap>` attributes.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub actionmap_attrs: IndexMap<String, Vec<(String, String)>>,
+    /// actionmap name → whole `<action>` elements for actions we don't load
+    /// (skipped maps, actions removed from the defaults, ...).
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub unknown_actions: IndexMap<String, Vec<XmlElement>>,
+    /// action id (`map.action`) → unrecognized parts of a known action.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub actions: IndexMap<String, ActionPassthrough>,
+}
+
+impl ProfilePassthrough {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == ProfilePassthrough::default()
+    }
+
+    /// First preserved top-level element named `name`.
+    #[inline]
+    pub fn element(&self, name: &str) -> Option<&XmlElement> {
+        self.elements.iter().find(|e| e.name == name)
+    }
+}
+
+/// Attributes of `node` whose names aren't in `known`, in source order.
+pub(crate) fn unknown_attrs(node: Node, known: &[&str]) -> Vec<(String, String)> {
+    node.attributes()
+        .filter(|a| !known.contains(&a.name()))
+        .map(|a| (a.name().to_string(), a.value().to_string()))
+        .collect()
+}
@@ -0,0 +1,212 @@
+//! Differences between two sets of bindings (e.g. two custom profiles on the same defaults).
+//!
+//! Actions are compared by the binds the game actually uses (custom over default, per
+//! device). Binds are compared by input only; a bind whose activation mode changed shows
+//! up as removed under the old mode and added under the new one.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    CoreLog,
+    bindings::{
+        action_binding::ActionBinding,
+        action_bindings::ActionBindings,
+        bind::DeviceKind,
+        constants::{ACTION_MAP_UI_CATEGORIES, SKIP_ACTION_MAPS},
+    },
+};
+
+/// What happened to an action between the two sides.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    /// Unbound (or missing) before, bound after.
+    Added,
+    /// Bound before, unbound (or missing) after.
+    Removed,
+    /// Bound on both sides, with different binds.
+    Changed,
+}
+
+impl DiffStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiffStatus::Added => "added",
+            DiffStatus::Removed => "removed",
+            DiffStatus::Changed => "changed",
+        }
+    }
+}
+
+/// Bind changes of one action on one device under one activation mode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BindChanges {
+    pub device: DeviceKind,
+    /// Activation mode name (`None` if neither the binds nor the action set one).
+    pub activation_mode: Option<String>,
+    /// Binds only on the new side, e.g. "lalt+f5".
+    pub added: Vec<String>,
+    /// Binds only on the old side.
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionDiff {
+    pub action_id: Arc<str>,
+    pub status: DiffStatus,
+    /// By device, then activation mode.
+    pub changes: Vec<BindChanges>,
+}
+
+/// Every action whose binds differ, in profile order (old side first, then actions only
+/// the new side has).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProfileDiff {
+    pub actions: Vec<ActionDiff>,
+}
+
+/// Active binds of one action: (device, mode) → bind strings.
+type BindSet = BTreeMap<(DeviceKind, Option<String>), BTreeSet<String>>;
+
+impl ProfileDiff {
+    /// Compare `old` against `new`.
+    pub fn between(old: &ActionBindings, new: &ActionBindings) -> Self {
+        let mut ids: Vec<&Arc<str>> = Vec::new();
+        let mut seen = HashSet::new();
+        for ab in [old, new] {
+            for action in ab.action_maps.values().flat_map(|m| m.actions.values()) {
+                if seen.insert(&action.action_id) {
+                    ids.push(&action.action_id);
+                }
+            }
+        }
+
+        let actions = ids
+            .into_iter()
+            .filter_map(|id| action_diff(id, old.get_action(id), old, new.get_action(id), new))
+            .collect();
+
+        ProfileDiff { actions }
+    }
+
+    /// Compare two profile files. Each side is either a bindings JSON cache (`.json`) or
+    /// a custom profile XML applied over `default_profile` (required for XML sides).
+    pub fn between_files(
+        old: &Path,
+        new: &Path,
+        default_profile: Option<&Path>,
+        logger: &Arc<dyn CoreLog>,
+    ) -> Result<Self, String> {
+        // Defaults are parsed once and shared by both XML sides
+        let mut defaults: Option<ActionBindings> = None;
+        let mut load = |path: &Path| -> Result<ActionBindings, String> {
+            if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+            {
+                let text = fs::read_to_string(path)
+                    .map_err(|e| format!("read {}: {e}", path.display()))?;
+                return ActionBindings::from_json(&text, logger);
+            }
+            if defaults.is_none() {
+                let default_profile = default_profile.ok_or_else(|| {
+                    format!(
+                        "{}: a default profile is needed to read XML",
+                        path.display()
+                    )
+                })?;
+                let mut ab = ActionBindings::default();
+                ab.load_default_profile(
+                    default_profile,
+                    &SKIP_ACTION_MAPS,
+                    &ACTION_MAP_UI_CATEGORIES,
+                    logger,
+                )?
+                .log(logger);
+                defaults = Some(ab);
+            }
+            let mut ab = defaults.clone().unwrap_or_default();
+            ab.apply_custom_profile(path, logger)?.log(logger);
+            ab.activation.rebuild_indexes();
+            Ok(ab)
+        };
+
+        let old = load(old)?;
+        let new = load(new)?;
+        Ok(Self::between(&old, &new))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Number of actions with `status`.
+    pub fn count(&self, status: DiffStatus) -> usize {
+        self.actions.iter().filter(|a| a.status == status).count()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize diff: {e}"))
+    }
+}
+
+/// Diff of one action, `None` if its binds are the same on both sides. `before` and
+/// `after` may have different ids (renamed actions).
+pub(crate) fn action_diff(
+    id: &Arc<str>,
+    before: Option<&ActionBinding>,
+    old: &ActionBindings,
+    after: Option<&ActionBinding>,
+    new: &ActionBindings,
+) -> Option<ActionDiff> {
+    let before = before.map(|a| active_binds(a, old)).unwrap_or_default();
+    let after = after.map(|a| active_binds(a, new)).unwrap_or_default();
+    if before == after {
+        return None;
+    }
+
+    let keys: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+    let empty = BTreeSet::new();
+    let changes: Vec<BindChanges> = keys
+        .into_iter()
+        .filter_map(|key| {
+            let b = before.get(key).unwrap_or(&empty);
+            let a = after.get(key).unwrap_or(&empty);
+            let added: Vec<String> = a.difference(b).cloned().collect();
+            let removed: Vec<String> = b.difference(a).cloned().collect();
+            (!added.is_empty() || !removed.is_empty()).then(|| BindChanges {
+                device: key.0,
+                activation_mode: key.1.clone(),
+                added,
+                removed,
+            })
+        })
+        .collect();
+
+    let status = match (before.is_empty(), after.is_empty()) {
+        (true, _) => DiffStatus::Added,
+        (_, true) => DiffStatus::Removed,
+        _ => DiffStatus::Changed,
+    };
+    Some(ActionDiff {
+        action_id: id.clone(),
+        status,
+        changes,
+    })
+}
+
+fn active_binds(action: &ActionBinding, ab: &ActionBindings) -> BindSet {
+    let mut out = BindSet::new();
+    for (device, b, _) in action.active_binds() {
+        out.entry((device, action.mode_name(b, &ab.activation)))
+            .or_default()
+            .insert(b.to_string());
+    }
+    out
+}
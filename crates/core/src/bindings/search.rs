@@ -0,0 +1,118 @@
+//! Case-insensitive, fuzzy action lookup over ids, translated labels and descriptions.
+//!
+//! Every word of the query has to match one of the fields as a substring or, in ids and
+//! labels, as a loose subsequence ("lndgr" finds "landing gear"). Lower scores are
+//! better: exact/prefix matches beat substrings, substrings beat subsequences, and
+//! descriptions rank below ids and labels.
+
+use std::collections::HashMap;
+
+use crate::bindings::{
+    action_binding::ActionBinding, action_bindings::ActionBindings, translations::get_translation,
+};
+
+/// Penalty for matching in the description instead of the id or label.
+const DESCRIPTION_PENALTY: u32 = 2;
+/// Base score of a subsequence (non-contiguous) match.
+const FUZZY_BASE: u32 = 10;
+
+pub struct SearchHit<'a> {
+    pub action: &'a ActionBinding,
+    /// Translated label (`ActionBinding::get_label`).
+    pub label: String,
+    /// Lower is better.
+    pub score: u32,
+}
+
+/// Actions matching `query`, best first (ties keep profile order). An empty query
+/// matches nothing.
+pub fn search_actions<'a>(
+    bindings: &'a ActionBindings,
+    translations: &HashMap<String, String>,
+    query: &str,
+) -> Vec<SearchHit<'a>> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<SearchHit> = bindings
+        .action_maps
+        .values()
+        .flat_map(|m| m.actions.values())
+        .filter_map(|action| {
+            let label = action.get_label(translations);
+            let description = action
+                .ui_description
+                .as_deref()
+                .map(|d| get_translation(d, translations).to_lowercase())
+                .unwrap_or_default();
+            let names = [action.action_id.to_lowercase(), label.to_lowercase()];
+            let score = words.iter().try_fold(0, |total, word| {
+                let best = names
+                    .iter()
+                    .filter_map(|text| match_score(text, word, true))
+                    .chain(match_score(&description, word, false).map(|s| s + DESCRIPTION_PENALTY))
+                    .min()?;
+                Some(total + best)
+            })?;
+            Some(SearchHit {
+                action,
+                label,
+                score,
+            })
+        })
+        .collect();
+
+    hits.sort_by_key(|h| h.score);
+    hits
+}
+
+/// Score of `word` in `text` (both lowercase), `None` if it doesn't occur.
+fn match_score(text: &str, word: &str, fuzzy: bool) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+    if text == word {
+        return Some(0);
+    }
+    if text.starts_with(word) {
+        return Some(1);
+    }
+    if let Some(pos) = text.find(word) {
+        // After a separator ("v_flightready", "landing gear") beats mid-word
+        let boundary = text[..pos].ends_with(|c: char| !c.is_alphanumeric());
+        return Some(if boundary { 2 } else { 3 });
+    }
+    if !fuzzy {
+        return None;
+    }
+    // Too scattered to be what was meant
+    let gaps = subsequence_gaps(text, word).filter(|&g| g as usize <= 2 * word.len())?;
+    Some(FUZZY_BASE + gaps)
+}
+
+/// Fewest characters skipped to find `word` in order within `text`, starting at the
+/// beginning of a word of `text` (so "lndgr" finds "landing gear" but not "salvage_nudge_right").
+fn subsequence_gaps(text: &str, word: &str) -> Option<u32> {
+    let first = word.chars().next()?;
+    let rest: Vec<char> = word.chars().skip(1).collect();
+    let chars: Vec<char> = text.chars().collect();
+
+    (0..chars.len())
+        .filter(|&i| chars[i] == first && (i == 0 || !chars[i - 1].is_alphanumeric()))
+        .filter_map(|start| {
+            let mut gaps = 0;
+            let mut it = chars[start + 1..].iter();
+            for w in &rest {
+                loop {
+                    if it.next()? == w {
+                        break;
+                    }
+                    gaps += 1;
+                }
+            }
+            Some(gaps)
+        })
+        .min()
+}
@@ -94,6 +94,18 @@ fn collect_translation_keys_from_bindings(bindings: &ActionBindings) -> HashSet<
             maybe_collect_tokens(ab.ui_label.as_deref(), &mut out);
             maybe_collect_tokens(ab.ui_description.as_deref(), &mut out);
             maybe_collect_tokens(ab.category.as_deref(), &mut out);
+            for st in &ab.states {
+                maybe_collect_tokens(st.ui_label.as_deref(), &mut out);
+            }
+        }
+    }
+
+    // Option group labels (axis tuning)
+    for tree in &bindings.options {
+        let mut stack: Vec<_> = tree.groups.iter().collect();
+        while let Some(g) = stack.pop() {
+            maybe_collect_tokens(g.ui_label.as_deref(), &mut out);
+            stack.extend(&g.children);
         }
     }
 
@@ -247,7 +259,8 @@ fn parse_line(line: &str) -> Option<(&str, &str)> {
     None
 }
 
-pub(crate) fn get_translation<'a>(
+/// Translation of a label key (with or without '@'); the key itself if there is none.
+pub fn get_translation<'a>(
     key: &'a str,
     translations: &'a std::collections::HashMap<String, String>,
 ) -> &'a str {
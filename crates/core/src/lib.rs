@@ -19,21 +19,39 @@ pub mod prelude {
     pub use crate::core_log::CoreLog;
 
     // Bindings graph
-    pub use crate::bindings::action_binding::ActionBinding;
+    pub use crate::bindings::action_binding::{ActionBinding, ActionState};
     pub use crate::bindings::action_bindings::ActionBindings;
+    pub use crate::bindings::action_group::{ACTION_GROUP_PREFIX, ActionGroup};
     pub use crate::bindings::action_map::ActionMap;
     pub use crate::bindings::activation_mode::{ActivationArena, ActivationMode};
-    pub use crate::bindings::bind::{Bind, BindMain, BindOrigin, Key, MouseButton};
+    pub use crate::bindings::bind::{
+        Bind, BindMain, BindOrigin, BindSource, DeviceKind, DeviceRef, Key, MouseButton,
+    };
+    pub use crate::bindings::bind_cost::CostWeights;
+    pub use crate::bindings::bindgen_config::{BINDGEN_CONFIG_VERSION, BindGenConfig};
     pub use crate::bindings::binds::Binds;
     pub use crate::bindings::binds_generator::BindGenerator;
+    pub use crate::bindings::changelog::{DefaultsChangelog, Rename, VersionBump};
+    pub use crate::bindings::cheat_sheet::{
+        CheatSheet, SheetFormat, SheetOptions, SheetRow, SheetSection,
+    };
+    pub use crate::bindings::conflicts::{BindConflict, ConflictReport, ConflictSide};
     pub use crate::bindings::constants::{
-        ACTION_MAP_UI_CATEGORIES, CANDIDATE_KEYS, CANDIDATE_MODIFIERS, CATEGORY_GROUPS,
-        DEFAULT_CATEGORY, DENY_COMBOS, DENY_MODIFIERS_PER_CATEGORY, SKIP_ACTION_MAPS,
+        ACTION_MAP_UI_CATEGORIES, CANDIDATE_KEY_ORDER, CANDIDATE_KEYS, CANDIDATE_MODIFIER_ORDER,
+        CANDIDATE_MODIFIERS, CATEGORY_GROUPS, DEFAULT_CATEGORY, DENY_COMBOS,
+        DENY_MODIFIERS_PER_CATEGORY, SKIP_ACTION_MAPS, category_group,
     };
+    pub use crate::bindings::heatmap::{HeatmapLayer, KeyUse, KeyboardHeatmap};
+    pub use crate::bindings::index::{BindUse, BindingsIndex, resolve_context};
+    pub use crate::bindings::load_report::{LoadIssue, LoadIssueKind, LoadReport};
+    pub use crate::bindings::options::{CurvePoint, OptionGroup, OptionTree, UiShow};
+    pub use crate::bindings::profile_diff::{ActionDiff, BindChanges, DiffStatus, ProfileDiff};
+    pub use crate::bindings::search::{SearchHit, search_actions};
 
     // Profile I/O helpers
     pub use crate::sc::profiles::{
-        appdata_dir, bindings_cache_path, load_bindings_from_appdata, parse_bindings_from_install,
+        appdata_dir, bindgen_config_path, bindings_cache_path, deck_actions_path,
+        load_bindgen_config, load_bindings_from_appdata, parse_bindings_from_install,
         resolve_custom_profile_from_root, save_bindings_profile_and_cache,
     };
 
@@ -7,7 +7,9 @@ use chrono::Local;
 use crate::CoreLog;
 use crate::bindings::{
     action_bindings::ActionBindings,
+    bindgen_config::BindGenConfig,
     constants::{ACTION_MAP_UI_CATEGORIES, SKIP_ACTION_MAPS},
+    load_report::LoadReport,
 };
 use crate::sc::install::GameInstallType;
 
@@ -26,12 +28,13 @@ pub fn resolve_custom_profile_from_root(root: &Path) -> Option<PathBuf> {
 
 /// Load ActionBindings from the game’s default profile, and (optionally) overlay the custom profile.
 /// This uses the shared skip/category constants and rebuilds the activation indexes.
+/// Also returns the load report of each file read (defaults first).
 pub fn parse_bindings_from_install(
     resource_dir: &Path, // where defaultProfile.xml lives
     game_root: &Path,    // install root for resolving custom
     include_custom: bool,
     logger: &Arc<dyn CoreLog>,
-) -> Result<ActionBindings, String> {
+) -> Result<(ActionBindings, Vec<LoadReport>), String> {
     let default_profile = resource_dir.join("defaultProfile.xml");
     if !default_profile.is_file() {
         return Err(format!(
@@ -47,17 +50,18 @@ pub fn parse_bindings_from_install(
     };
 
     let mut ab = ActionBindings::default();
-    ab.load_default_profile(
+    let mut reports = vec![ab.load_default_profile(
         &default_profile,
         &SKIP_ACTION_MAPS,
         &ACTION_MAP_UI_CATEGORIES,
         logger,
-    )?;
+    )?];
 
     if let Some(cf) = custom_file {
         if cf.try_exists().unwrap_or(false) {
-            if let Err(e) = ab.apply_custom_profile(&cf, logger) {
-                logger.warn(&format!("apply_custom_profile({}): {}", cf.display(), e));
+            match ab.apply_custom_profile(&cf, logger) {
+                Ok(report) => reports.push(report),
+                Err(e) => logger.warn(&format!("apply_custom_profile({}): {}", cf.display(), e)),
             }
         } else {
             logger.debug(&format!("no custom file at {}", cf.display()));
@@ -65,7 +69,7 @@ pub fn parse_bindings_from_install(
     }
 
     ab.activation.rebuild_indexes();
-    Ok(ab)
+    Ok((ab, reports))
 }
 
 /// Compute the AppData folder for this plugin.
@@ -81,6 +85,31 @@ pub fn bindings_cache_path(plugin_id: &str, ty: GameInstallType) -> Result<PathB
     Ok(appdata_dir(plugin_id)?.join(format!("bindings_{}.json", ty.name())))
 }
 
+/// Return `<AppData>/<plugin_id>/deck_actions.json` (does not create it).
+pub fn deck_actions_path(plugin_id: &str) -> Result<PathBuf, String> {
+    Ok(appdata_dir(plugin_id)?.join("deck_actions.json"))
+}
+
+/// Return `<AppData>/<plugin_id>/bindgen.json` (does not create it).
+pub fn bindgen_config_path(plugin_id: &str) -> Result<PathBuf, String> {
+    Ok(appdata_dir(plugin_id)?.join("bindgen.json"))
+}
+
+/// Load the bind-generation config from AppData; the built-in rules if there is none.
+pub fn load_bindgen_config(
+    plugin_id: &str,
+    logger: &Arc<dyn CoreLog>,
+) -> Result<BindGenConfig, String> {
+    let file = bindgen_config_path(plugin_id)?;
+    if !file.try_exists().unwrap_or(false) {
+        logger.debug(&format!("No bindgen config at {}", file.display()));
+        return Ok(BindGenConfig::default());
+    }
+    let cfg = BindGenConfig::load(&file)?;
+    logger.info(&format!("Loaded bindgen config from {}", file.display()));
+    Ok(cfg)
+}
+
 /// Load ActionBindings from the AppData JSON cache for an install.
 pub fn load_bindings_from_appdata(
     plugin_id: &str,
@@ -1,6 +1,9 @@
 use crate::{
     PLUGIN_ID,
-    state::{action_bindings_store::ActionBindingsStore, resource_dir_store::ResourceDir},
+    state::{
+        action_bindings_store::ActionBindingsStore, deck_actions_store::DeckActions,
+        resource_dir_store::ResourceDir,
+    },
     topics::{ACTIONS_CACHE_UPDATED, EXEC_SEND, ExecSend},
     util::{
         core_log::PluginCoreLog,
@@ -115,11 +118,25 @@ impl Action for ScAction {
         build_pi_items(cx, ev.context);
     }
 
-    fn will_appear(&mut self, _cx: &Context, _ev: &WillAppear) {
+    fn will_appear(&mut self, cx: &Context, ev: &WillAppear) {
         self.down_at = None;
         self.long_cancel = Arc::new(AtomicBool::new(false));
         self.long_fired = Arc::new(AtomicBool::new(false));
         self.short_fired_on_down = false;
+        record_deck_actions(cx, ev.context, ev.device, ev.settings);
+    }
+
+    fn will_disappear(&mut self, cx: &Context, ev: &WillDisappear) {
+        // Deleted, reassigned or paged away: no longer on the deck until it appears again
+        if let Some(deck) = cx.try_ext::<DeckActions>() {
+            if let Err(e) = deck.remove(ev.context) {
+                warn!(cx.log(), "forget deck actions: {}", e);
+            }
+        }
+    }
+
+    fn did_receive_settings(&mut self, cx: &Context, ev: &DidReceiveSettings) {
+        record_deck_actions(cx, ev.context, ev.device, ev.settings);
     }
 
     fn key_down(&mut self, cx: &Context, ev: &KeyDown) {
@@ -250,6 +267,27 @@ impl Action for ScAction {
     }
 }
 
+/// Remember which actions this instance triggers (they get first pick when generating binds).
+fn record_deck_actions(cx: &Context, cx_id: &str, device: &str, settings: &Map<String, Value>) {
+    let Some(deck) = cx.try_ext::<DeckActions>() else {
+        return;
+    };
+    match ScActionSettings::from_map(settings) {
+        Ok(s) => {
+            let ids = s
+                .short_id
+                .into_iter()
+                .chain(s.long_id)
+                .filter(|id| !id.is_empty())
+                .collect();
+            if let Err(e) = deck.set(cx_id, device, ids) {
+                warn!(cx.log(), "record_deck_actions: {}", e);
+            }
+        }
+        Err(e) => debug!(cx.log(), "record_deck_actions: bad settings: {}", e),
+    }
+}
+
 fn build_pi_items(cx: &Context, cx_id: &str) {
     let resource_dir = match cx.try_ext::<ResourceDir>() {
         Some(dir) => dir.get(),
@@ -297,18 +335,34 @@ fn build_pi_items(cx: &Context, cx_id: &str) {
             .actions
             .values()
             .map(|ab| {
+                // Toggles read better as their states ("Enable / Disable ...")
+                let label = ab
+                    .get_states_label(&translations)
+                    .unwrap_or_else(|| ab.get_label(&translations));
                 Item::with_label(
                     ab.action_id.to_string(),
-                    format!(
-                        "{} [{}]",
-                        ab.get_label(&translations),
-                        ab.get_binds_label().unwrap_or_default()
-                    ),
+                    format!("{} [{}]", label, ab.get_binds_label().unwrap_or_default()),
                 )
             })
             .collect();
         DataSourceResult::ItemGroup(ItemGroup::new(am.get_label(&translations), children))
     }));
+    if !bindings.action_groups.is_empty() {
+        let children: Vec<Item> = bindings
+            .action_groups
+            .values()
+            .map(|g| {
+                Item::with_label(
+                    g.target_id(),
+                    format!("{} ({} actions)", g.name, g.members.len()),
+                )
+            })
+            .collect();
+        items.push(DataSourceResult::ItemGroup(ItemGroup::new(
+            "Action Groups",
+            children,
+        )));
+    }
     cx.sd().send_to_property_inspector(
         cx_id,
         json!({
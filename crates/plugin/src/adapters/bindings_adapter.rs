@@ -3,20 +3,22 @@ use std::sync::Arc;
 use streamdeck_lib::prelude::*;
 use streamdeck_sc_core::{
     CoreLog,
-    prelude::ActionBindings,
+    prelude::{ActionBindings, BindGenConfig, LoadReport},
     sc::profiles::{
-        load_bindings_from_appdata, parse_bindings_from_install, save_bindings_profile_and_cache,
+        load_bindgen_config, load_bindings_from_appdata, parse_bindings_from_install,
+        save_bindings_profile_and_cache,
     },
 };
 
 use crate::{
     state::{
         action_bindings_store::ActionBindingsStore, active_install_store::ActiveInstall,
-        install_paths_store::InstallPaths, resource_dir_store::ResourceDir,
+        deck_actions_store::DeckActions, install_paths_store::InstallPaths,
+        resource_dir_store::ResourceDir,
     },
     topics::{
-        ACTIONS_REQUEST, BINDINGS_PARSED, BINDINGS_REBUILD_AND_SAVE, INITIAL_INSTALL_SCAN_DONE,
-        INSTALL_ACTIVE_CHANGED,
+        ACTIONS_REQUEST, BINDINGS_PARSED, BINDINGS_REBUILD_AND_SAVE, BindingsParsed,
+        INITIAL_INSTALL_SCAN_DONE, INSTALL_ACTIVE_CHANGED,
     },
     util::core_log::PluginCoreLog,
 };
@@ -88,6 +90,11 @@ impl Adapter for BindingsAdapter {
             .ok_or(AdapterError::Init("ActiveInstall ext missing".to_string()))?
             .clone();
 
+        let deck_actions = cx
+            .try_ext::<DeckActions>()
+            .ok_or(AdapterError::Init("DeckActions ext missing".to_string()))?
+            .clone();
+
         let plugin_id = self.plugin_id;
 
         let join = std::thread::spawn(move || {
@@ -108,7 +115,7 @@ impl Adapter for BindingsAdapter {
                                 debug!(sd_log, "BINDINGS_REBUILD_AND_SAVE for {:?}", m.ty);
 
                                 // Parse from files via core
-                                let mut ab = match parse_bindings_from_install(
+                                let (mut ab, reports) = match parse_bindings_from_install(
                                     &res_dir.get(),
                                     &game_root,
                                     m.with_custom,
@@ -120,9 +127,48 @@ impl Adapter for BindingsAdapter {
                                         continue;
                                     }
                                 };
+                                log_load_reports(&sd_log, &reports);
+
+                                // Generation rules from AppData/bindgen.json (built-in if absent)
+                                let mut config = load_bindgen_config(plugin_id, &core_log)
+                                    .unwrap_or_else(|e| {
+                                        warn!(sd_log, "bindgen config: {} (using built-in rules)", e);
+                                        BindGenConfig::default()
+                                    });
 
-                                // Fill gaps (CoreLog)
-                                ab.generate_missing_binds(&core_log);
+                                // Actions placed on the deck get first pick of the key pool
+                                let on_deck = deck_actions.action_ids();
+                                debug!(sd_log, "{} actions on the deck", on_deck.len());
+                                // priority_only without any priority actions would bind nothing
+                                let no_priorities = on_deck.is_empty()
+                                    && config.priority_actions.as_ref().is_none_or(|p| p.is_empty());
+                                if no_priorities && config.priority_only == Some(true) {
+                                    warn!(sd_log, "priority_only ignored: no actions on the deck and no priority_actions");
+                                    config.priority_only = None;
+                                }
+                                config
+                                    .priority_actions
+                                    .get_or_insert_with(Vec::new)
+                                    .extend(on_deck);
+
+                                // Fill gaps (CoreLog), keeping binds generated by the last run
+                                let previous = load_bindings_from_appdata(plugin_id, m.ty, &core_log)
+                                    .map_err(|e| debug!(sd_log, "no previous bindings cache: {}", e))
+                                    .ok();
+                                ab.generate_missing_binds_with(&config, previous.as_ref(), &core_log);
+
+                                let conflicts = ab.conflicts_with(&config);
+                                for c in conflicts.user_conflicts() {
+                                    warn!(
+                                        sd_log,
+                                        "bind conflict {}: {} ({}) vs {} ({})",
+                                        c.bind,
+                                        c.first.action_id,
+                                        c.first.origin,
+                                        c.second.action_id,
+                                        c.second.origin
+                                    );
+                                }
 
                                 // Write XML profile + JSON cache via core
                                 if let Err(e) = save_bindings_profile_and_cache(
@@ -140,7 +186,7 @@ impl Adapter for BindingsAdapter {
                                 // Publish snapshot
                                 debug!(sd_log, "Storing ActionBindings in store");
                                 store.replace(ab);
-                                bus.publish_t(BINDINGS_PARSED, ());
+                                bus.publish_t(BINDINGS_PARSED, BindingsParsed { ty: m.ty, reports });
                                 continue;
                             }
 
@@ -200,7 +246,10 @@ impl Adapter for BindingsAdapter {
                                         true, // include_custom
                                         &core_log,
                                     ) {
-                                        Ok(v) => ab = v,
+                                        Ok((v, reports)) => {
+                                            log_load_reports(&sd_log, &reports);
+                                            ab = v;
+                                        }
                                         Err(e) => {
                                             warn!(sd_log, "parse fallback {:?}: {}", m.ty, e);
                                             continue;
@@ -228,3 +277,17 @@ impl Adapter for BindingsAdapter {
         Ok(AdapterHandle::from_crossbeam(join, stop_tx))
     }
 }
+
+/// One warning per summary line ("custom.xml: 12 custom rebinds reference ...");
+/// the individual issues go to the log at debug level.
+fn log_load_reports(logger: &Arc<dyn ActionLog>, reports: &[LoadReport]) {
+    for report in reports {
+        let file = report.path.display();
+        for line in report.summary() {
+            warn!(logger, "{}: {}", file, line);
+        }
+        for issue in &report.issues {
+            debug!(logger, "  {}:{}", file, issue);
+        }
+    }
+}
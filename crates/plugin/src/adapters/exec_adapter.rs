@@ -1,6 +1,7 @@
 use crossbeam_channel::{Receiver as CbReceiver, bounded, select};
 use std::{sync::Arc, time::Duration};
 use streamdeck_lib::prelude::*;
+use streamdeck_sc_core::bindings::action_group::ACTION_GROUP_PREFIX;
 
 use crate::simulate::SimulateExt;
 use crate::{
@@ -78,14 +79,29 @@ fn handle_exec(
     logger: &Arc<dyn ActionLog>,
     msg: &ExecSend,
 ) -> Result<(), String> {
-    let action = store
-        .get_binding_by_id(&msg.action_id)
-        .ok_or_else(|| format!("action '{}' not found", msg.action_id))?;
-
     let hold_ms = msg.hold_ms.map(Duration::from_millis);
-    let bindings = store.snapshot();
+    let index = store.index();
+    let bindings = index.bindings();
+
+    // `group:<name>` fires the members of an action group in order, each distinct bind once
+    if msg.action_id.starts_with(ACTION_GROUP_PREFIX) {
+        let actions = index.resolve_presses(&msg.action_id);
+        if actions.is_empty() {
+            return Err(format!("action group '{}' not found", msg.action_id));
+        }
+        for action in actions {
+            action
+                .simulate_using(Arc::clone(logger), hold_ms, msg.is_down, bindings)
+                .map_err(|e| format!("simulate {}: {e}", action.action_id))?;
+        }
+        return Ok(());
+    }
+
+    let action = index
+        .get(&msg.action_id)
+        .ok_or_else(|| format!("action '{}' not found", msg.action_id))?;
 
     action
-        .simulate_using(Arc::clone(logger), hold_ms, msg.is_down, &bindings)
+        .simulate_using(Arc::clone(logger), hold_ms, msg.is_down, bindings)
         .map_err(|e| format!("simulate: {e}"))
 }
@@ -1,6 +1,7 @@
 use std::{process::exit, sync::Arc};
 
 use streamdeck_lib::prelude::*;
+use streamdeck_sc_core::sc::profiles::deck_actions_path;
 
 use crate::{
     actions::{
@@ -13,7 +14,8 @@ use crate::{
     },
     state::{
         action_bindings_store::ActionBindingsStore, active_install_store::ActiveInstall,
-        install_paths_store::InstallPaths, resource_dir_store::ResourceDir,
+        deck_actions_store::DeckActions, install_paths_store::InstallPaths,
+        resource_dir_store::ResourceDir,
     },
 };
 
@@ -51,12 +53,29 @@ fn main() {
         }
     };
 
+    // Actions placed on the deck, as saved by the last session
+    let deck_actions = match deck_actions_path(PLUGIN_ID) {
+        Ok(file) => DeckActions::load(file),
+        Err(e) => {
+            warn!(logger, "Deck actions won't be saved: {e}");
+            DeckActions::default()
+        }
+    };
+
     let hooks = AppHooks::default().append(|cx, ev| {
         use streamdeck_lib::prelude::HookEvent::*;
         match ev {
             ApplicationDidLaunch { .. } => {
                 cx.bus().publish_t(topics::INSTALL_SCAN, ());
             }
+            DeviceDidDisconnect { device, .. } => {
+                // Its instances won't send will_disappear any more
+                if let Some(deck) = cx.try_ext::<DeckActions>() {
+                    if let Err(e) = deck.remove_device(device) {
+                        warn!(cx.log(), "forget deck actions of {}: {}", device, e);
+                    }
+                }
+            }
             _ => {
                 debug!(cx.log(), "HookEvent: {:?}", ev);
             }
@@ -69,6 +88,7 @@ fn main() {
         .add_extension(Arc::new(ResourceDir::new(resource_dir)))
         .add_extension(Arc::new(InstallPaths::default()))
         .add_extension(Arc::new(ActiveInstall::default()))
+        .add_extension(Arc::new(deck_actions))
         .add_adapter(InstallScannerAdapter::new())
         .add_adapter(BindingsAdapter::new(PLUGIN_ID))
         .add_adapter(ExecAdapter::new())
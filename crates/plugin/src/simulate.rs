@@ -4,7 +4,7 @@ use streamdeck_lib::prelude::*;
 
 use streamdeck_sc_core::bindings::{
     action_binding::ActionBinding,
-    activation_mode::ActivationArena,
+    activation_mode::{ActivationArena, ActivationMode},
     bind::{BindMain, Key as CoreKey},
 };
 
@@ -34,22 +34,10 @@ impl SimulateExt for ActionBinding {
         is_down_override: Option<bool>,
         modes: &ActivationArena,
     ) -> Result<(), String> {
-        // ---- pick the first runnable bind (prefers keyboard) ----
-        let pick_first_runnable = |binds: &streamdeck_sc_core::bindings::binds::Binds| {
-            binds
-                .keyboard
-                .iter()
-                .chain(binds.mouse.iter())
-                .find(|b| !b.is_unbound && b.is_executable())
-                .cloned()
-        };
-
-        let bind = if let Some(cb) = self.custom_binds.as_ref() {
-            pick_first_runnable(cb).or_else(|| pick_first_runnable(&self.default_binds))
-        } else {
-            pick_first_runnable(&self.default_binds)
-        }
-        .ok_or_else(|| "No executable bind found (only wheel/axis/HMD or unbound)".to_string())?;
+        // First runnable bind in effect (prefers keyboard; custom per device over defaults)
+        let bind = self.executable_bind().ok_or_else(|| {
+            "No executable bind found (only wheel/axis/HMD or unbound)".to_string()
+        })?;
 
         // ---- activation mode ----
         let am_ix = bind
@@ -99,9 +87,10 @@ impl SimulateExt for ActionBinding {
             if let Some(ov) = hold_duration_override {
                 return (ov.as_millis() as u64).saturating_add(50);
             }
-            let base_ms = if let Some(th) = mode.press_trigger_threshold {
+            // -1 means "disabled" in SC XML; treat it like unset
+            let base_ms = if let Some(th) = ActivationMode::active(mode.press_trigger_threshold) {
                 if th > 0.0 { (th * 1000.0) as u64 } else { 260 }
-            } else if let Some(d) = mode.hold_trigger_delay {
+            } else if let Some(d) = ActivationMode::active(mode.hold_trigger_delay) {
                 if d > 0.0 { (d * 1000.0) as u64 } else { 260 }
             } else {
                 260
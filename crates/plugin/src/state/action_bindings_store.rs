@@ -2,11 +2,12 @@ use arc_swap::ArcSwap;
 use std::sync::Arc;
 use streamdeck_lib::prelude::*;
 
-use streamdeck_sc_core::bindings::action_binding::ActionBinding;
 use streamdeck_sc_core::bindings::action_bindings::ActionBindings;
+use streamdeck_sc_core::bindings::index::BindingsIndex;
 
+/// Current bindings plus their reverse-lookup index, swapped together.
 pub struct ActionBindingsStore {
-    inner: Arc<ArcSwap<ActionBindings>>,
+    inner: Arc<ArcSwap<BindingsIndex>>,
     logger: Arc<dyn ActionLog>,
 }
 
@@ -22,32 +23,25 @@ impl Clone for ActionBindingsStore {
 impl ActionBindingsStore {
     pub fn new(logger: Arc<dyn ActionLog>) -> Self {
         Self {
-            inner: Arc::new(ArcSwap::from_pointee(ActionBindings::default())),
+            inner: Arc::new(ArcSwap::from_pointee(BindingsIndex::default())),
             logger,
         }
     }
 
     pub fn snapshot(&self) -> Arc<ActionBindings> {
+        Arc::clone(self.inner.load().bindings())
+    }
+
+    /// Index over the current snapshot (lookups borrow from it instead of cloning).
+    pub fn index(&self) -> Arc<BindingsIndex> {
         self.inner.load_full()
     }
 
     pub fn replace(&self, new_ab: ActionBindings) {
-        self.inner.store(Arc::new(new_ab));
+        self.inner.store(Arc::new(new_ab.into_index()));
     }
 
     pub fn clear(&self) {
-        self.inner.store(Arc::new(ActionBindings::default()));
-    }
-
-    pub fn get_binding_by_id(&self, id: &str) -> Option<ActionBinding> {
-        let (map, action) = {
-            let mut parts = id.splitn(2, '.');
-            (parts.next()?, parts.next()?)
-        };
-        let snap = self.snapshot();
-        snap.action_maps
-            .get(map)
-            .and_then(|m| m.actions.get(action))
-            .cloned()
+        self.inner.store(Arc::new(BindingsIndex::default()));
     }
 }
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Action ids configured on one ScAction instance (`actionShort`/`actionLong`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DeckInstance {
+    /// Stream Deck device the instance is on.
+    device: String,
+    ids: Vec<String>,
+}
+
+/// Action ids configured on the ScAction instances currently shown, by context.
+///
+/// Filled as instances appear or their settings change, emptied as they disappear
+/// (deleted, reassigned, or their page or profile switched away) and swept when their
+/// device goes away. Saved to AppData so a rebuild right after startup still knows the
+/// instances shown last.
+#[derive(Clone, Default)]
+pub struct DeckActions {
+    instances: Arc<RwLock<BTreeMap<String, DeckInstance>>>,
+    /// Where the set is saved (`None`: memory only).
+    file: Option<PathBuf>,
+    /// Held from snapshot to rename so saves land in order.
+    save: Arc<Mutex<()>>,
+}
+
+impl DeckActions {
+    /// Set saved at `file`, starting from its content (empty if missing or unreadable).
+    pub fn load(file: PathBuf) -> Self {
+        let instances = fs::read_to_string(&file)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            instances: Arc::new(RwLock::new(instances)),
+            file: Some(file),
+            save: Arc::default(),
+        }
+    }
+
+    /// Record the ids of one instance and save the set if it changed.
+    pub fn set(&self, context: &str, device: &str, ids: Vec<String>) -> Result<(), String> {
+        let instance = DeckInstance {
+            device: device.to_string(),
+            ids,
+        };
+        self.update(|m| {
+            if m.get(context) == Some(&instance) {
+                return false;
+            }
+            m.insert(context.to_string(), instance);
+            true
+        })
+    }
+
+    /// Forget one instance and save the set if it was known.
+    pub fn remove(&self, context: &str) -> Result<(), String> {
+        self.update(|m| m.remove(context).is_some())
+    }
+
+    /// Forget every instance on `device` and save the set if any were known.
+    pub fn remove_device(&self, device: &str) -> Result<(), String> {
+        self.update(|m| {
+            let before = m.len();
+            m.retain(|_, i| i.device != device);
+            m.len() != before
+        })
+    }
+
+    /// All referenced ids (action ids or `group:<name>`), deduped and sorted.
+    pub fn action_ids(&self) -> Vec<String> {
+        self.instances
+            .read()
+            .map(|r| {
+                r.values()
+                    .flat_map(|i| &i.ids)
+                    .cloned()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Apply `change` (true if it changed anything) and save the result. The file is
+    /// written after the map lock is released, through a temp file and a rename.
+    fn update(
+        &self,
+        change: impl FnOnce(&mut BTreeMap<String, DeckInstance>) -> bool,
+    ) -> Result<(), String> {
+        let Ok(_save) = self.save.lock() else {
+            return Ok(());
+        };
+        let json = {
+            let Ok(mut w) = self.instances.write() else {
+                return Ok(());
+            };
+            if !change(&mut w) || self.file.is_none() {
+                return Ok(());
+            }
+            serde_json::to_string_pretty(&*w).map_err(|e| format!("serialize: {e}"))?
+        };
+
+        let Some(file) = self.file.as_ref() else {
+            return Ok(());
+        };
+        let tmp = file.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|e| format!("write {}: {e}", tmp.display()))?;
+        fs::rename(&tmp, file).map_err(|e| format!("rename to {}: {e}", file.display()))
+    }
+}
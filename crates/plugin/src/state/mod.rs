@@ -1,4 +1,5 @@
 pub(crate) mod action_bindings_store;
 pub(crate) mod active_install_store;
+pub(crate) mod deck_actions_store;
 pub(crate) mod install_paths_store;
 pub(crate) mod resource_dir_store;
@@ -1,5 +1,5 @@
 use streamdeck_lib::prelude::*;
-use streamdeck_sc_core::prelude::GameInstallType;
+use streamdeck_sc_core::prelude::{GameInstallType, LoadReport};
 
 // Execute an action by ID (used by ExecAdapter)
 pub const EXEC_SEND: TopicId<ExecSend> = TopicId::new("sc.exec.send");
@@ -26,7 +26,15 @@ pub struct InstallActiveChanged {
 }
 
 // Bindings lifecycle
-pub const BINDINGS_PARSED: TopicId<()> = TopicId::new("sc.bindings.parsed");
+pub const BINDINGS_PARSED: TopicId<BindingsParsed> = TopicId::new("sc.bindings.parsed");
+
+#[derive(Debug, Clone)]
+pub struct BindingsParsed {
+    /// LIVE | PTU | TechPreview
+    pub ty: GameInstallType,
+    /// What parsing dropped or kept verbatim, one per profile read (defaults first)
+    pub reports: Vec<LoadReport>,
+}
 
 pub const BINDINGS_REBUILD_AND_SAVE: TopicId<BindingsRebuildAndSave> =
     TopicId::new("sc.bindings.rebuild-and-save");